@@ -0,0 +1,30 @@
+let nome: string = "João";
+console.log(nome);
+
+if (true) {
+    let idade: number = 25;
+    console.log(idade);
+} else {
+    console.log("Não é verdadeiro");
+}
+
+let contador: number = 0;
+while (contador < 5) {
+    console.log("Contador é " + contador);
+    contador = contador + 1;
+}
+
+const nomes: string[] = ["Jose", "Juan", "Guilherme", "Vinicius"];
+console.log("Array de nomes:", nomes);
+
+console.log("Operação:  ( 1 + 2 ) * 3");
+let resultado1: number = (1 + 2) * 3;
+console.log("Resultado:", resultado1);
+
+console.log("Operação: 10 / ( 2 + 3 )");
+let resultado2: number = 10 / (2 + 3);
+console.log("Resultado:", resultado2);
+
+console.log("Operação: ( 2 + 3 ) * ( 4 - 1 )");
+let resultado3: number = (2 + 3) * (4 - 1);
+console.log("Resultado:", resultado3);
@@ -1,49 +1,191 @@
+use logos::Span;
+use std::fmt;
+
+// Nó da AST acompanhado do trecho do código-fonte de onde veio
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Spanned { node, span }
+    }
+}
+
+pub type Stmt = Spanned<Statement>;
+pub type Expr = Spanned<Expression>;
+pub type Ident = Spanned<String>;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     String,
     Number,
     Boolean,
     Array(Box<Type>),
+    Void,
+    Function {
+        params: Vec<Type>,
+        return_type: Box<Type>,
+    },
+}
+
+// Exibe o tipo na sintaxe do TypeScript, para as mensagens de erro
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::String => write!(f, "string"),
+            Type::Number => write!(f, "number"),
+            Type::Boolean => write!(f, "boolean"),
+            Type::Array(inner) => match **inner {
+                Type::Function { .. } => write!(f, "({})[]", inner),
+                _ => write!(f, "{}[]", inner),
+            },
+            Type::Void => write!(f, "void"),
+            Type::Function {
+                params,
+                return_type,
+            } => {
+                let params = params
+                    .iter()
+                    .enumerate()
+                    .map(|(index, ty)| format!("arg{}: {}", index, ty))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "({}) => {}", params, return_type)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: Ident,
+    // Obrigatório em `function`; opcional nos parâmetros de arrow functions
+    pub type_annotation: Option<Type>,
+}
+
+// Tipo de uma função a partir da assinatura; desconhecido se faltar a
+// anotação de algum parâmetro ou do retorno.
+pub fn function_type(params: &[Parameter], return_type: &Option<Type>) -> Option<Type> {
+    let params = params
+        .iter()
+        .map(|param| param.type_annotation.clone())
+        .collect::<Option<Vec<_>>>()?;
+    Some(Type::Function {
+        params,
+        return_type: Box::new(return_type.clone()?),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrowBody {
+    Expression(Box<Expr>),
+    Block(Vec<Stmt>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub enum Statement {
-    ConsoleLog(Vec<Expression>),
+    // `console.log(...)`, `console.error(...)` etc.
+    Console {
+        method: ConsoleMethod,
+        args: Vec<Expr>,
+    },
     VariableDeclaration {
-        name: String,
-        type_annotation: Type,
-        value: Option<Expression>,
+        name: Ident,
+        // `const` em vez de `let`; não pode receber atribuições
+        is_const: bool,
+        // Quando omitido, é preenchido com o tipo inferido pelo `TypeChecker`
+        type_annotation: Option<Type>,
+        value: Option<Expr>,
     },
     IfStatement {
-        condition: Expression,
-        then_branch: Vec<Statement>,
-        else_branch: Option<Vec<Statement>>,
+        condition: Expr,
+        then_branch: Vec<Stmt>,
+        else_branch: Option<Vec<Stmt>>,
     },
     WhileStatement {
-        condition: Expression,
-        body: Vec<Statement>,
+        condition: Expr,
+        body: Vec<Stmt>,
     },
+    // `for (init; condition; update) { body }`; todas as partes do cabeçalho são opcionais
+    ForStatement {
+        init: Option<Box<Stmt>>,
+        condition: Option<Expr>,
+        update: Option<Expr>,
+        body: Vec<Stmt>,
+    },
+    // `for (const item of array) { body }`
+    ForOfStatement {
+        variable: Ident,
+        is_const: bool,
+        iterable: Expr,
+        body: Vec<Stmt>,
+    },
+    Break,
+    Continue,
     Assignment {
-        name: String,
-        value: Expression,
+        name: Ident,
+        // `Some(op)` nas atribuições compostas, como `x += 1`
+        op: Option<BinaryOperator>,
+        value: Expr,
+    },
+    FunctionDeclaration {
+        name: Ident,
+        params: Vec<Parameter>,
+        // `None` quando a função não declara o tipo de retorno
+        return_type: Option<Type>,
+        body: Vec<Stmt>,
     },
+    Return(Option<Expr>),
+    // Expressão usada como declaração, como uma chamada `f(x);`
+    Expression(Expr),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
     StringLiteral(String),
-    NumberLiteral(i32),
+    NumberLiteral(f64),
+    BooleanLiteral(bool),
     Identifier(String),
     BinaryOp {
-        left: Box<Expression>,
+        left: Box<Expr>,
         op: BinaryOperator,
-        right: Box<Expression>,
+        right: Box<Expr>,
     },
     Assignment {
-        name: String,
-        value: Box<Expression>,
+        name: Ident,
+        op: Option<BinaryOperator>,
+        value: Box<Expr>,
+    },
+    // `condition ? consequent : alternate`
+    Conditional {
+        condition: Box<Expr>,
+        consequent: Box<Expr>,
+        alternate: Box<Expr>,
+    },
+    // `++x`, `x++`, `--x` e `x--`
+    Update {
+        name: Ident,
+        op: UpdateOperator,
+        prefix: bool,
+    },
+    ArrayLiteral(Vec<Expr>),
+    Unary {
+        op: UnaryOperator,
+        operand: Box<Expr>,
+    },
+    Call {
+        callee: Box<Expr>,
+        args: Vec<Expr>,
+    },
+    ArrowFunction {
+        params: Vec<Parameter>,
+        return_type: Option<Type>,
+        body: ArrowBody,
     },
-    ArrayLiteral(Vec<Expression>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -52,6 +194,152 @@ pub enum BinaryOperator {
     Subtract,
     Multiply,
     Divide,
+    Modulo,
+    Exponent,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    UnsignedShiftRight,
     LessThan,
     GreaterThan,
+    LessEqual,
+    GreaterEqual,
+    // `==` e `!=`: no JavaScript convertem os operandos antes de comparar
+    Equal,
+    NotEqual,
+    StrictEqual,
+    StrictNotEqual,
+    // `&&` e `||`: avaliam o lado direito só quando necessário
+    And,
+    Or,
+}
+
+impl BinaryOperator {
+    // Precedência do operador no JavaScript; maior liga mais forte
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Or => 0,
+            BinaryOperator::And => 1,
+            BinaryOperator::BitOr => 2,
+            BinaryOperator::BitXor => 3,
+            BinaryOperator::BitAnd => 4,
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::StrictEqual
+            | BinaryOperator::StrictNotEqual => 5,
+            BinaryOperator::LessThan
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::LessEqual
+            | BinaryOperator::GreaterEqual => 6,
+            BinaryOperator::ShiftLeft
+            | BinaryOperator::ShiftRight
+            | BinaryOperator::UnsignedShiftRight => 7,
+            BinaryOperator::Add | BinaryOperator::Subtract => 8,
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => 9,
+            BinaryOperator::Exponent => 10,
+        }
+    }
+
+    // `a ** b ** c` é `a ** (b ** c)`
+    pub fn is_right_associative(&self) -> bool {
+        *self == BinaryOperator::Exponent
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(self.precedence(), 5 | 6)
+    }
+
+    pub fn is_logical(&self) -> bool {
+        matches!(self, BinaryOperator::And | BinaryOperator::Or)
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::Exponent => "**",
+            BinaryOperator::BitAnd => "&",
+            BinaryOperator::BitOr => "|",
+            BinaryOperator::BitXor => "^",
+            BinaryOperator::ShiftLeft => "<<",
+            BinaryOperator::ShiftRight => ">>",
+            BinaryOperator::UnsignedShiftRight => ">>>",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::StrictEqual => "===",
+            BinaryOperator::StrictNotEqual => "!==",
+            BinaryOperator::And => "&&",
+            BinaryOperator::Or => "||",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleMethod {
+    Log,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ConsoleMethod {
+    // `console.error` e `console.warn` escrevem na saída de erro
+    pub fn is_stderr(&self) -> bool {
+        matches!(self, ConsoleMethod::Warn | ConsoleMethod::Error)
+    }
+}
+
+impl fmt::Display for ConsoleMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConsoleMethod::Log => write!(f, "console.log"),
+            ConsoleMethod::Info => write!(f, "console.info"),
+            ConsoleMethod::Warn => write!(f, "console.warn"),
+            ConsoleMethod::Error => write!(f, "console.error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnaryOperator {
+    Not,
+    Negate,
+    BitNot,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateOperator {
+    Increment,
+    Decrement,
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnaryOperator::Not => write!(f, "!"),
+            UnaryOperator::Negate => write!(f, "-"),
+            UnaryOperator::BitNot => write!(f, "~"),
+        }
+    }
+}
+
+impl fmt::Display for UpdateOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UpdateOperator::Increment => write!(f, "++"),
+            UpdateOperator::Decrement => write!(f, "--"),
+        }
+    }
 }
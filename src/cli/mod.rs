@@ -0,0 +1,176 @@
+use crate::diagnostics::ColorChoice;
+use std::path::PathBuf;
+use thiserror::Error;
+
+pub const USAGE: &str = "\
+Uso: js-compiler <comando> [opções] <arquivos...>
+
+Comandos:
+    build    Compila os arquivos .ts em um crate Rust
+    run      Compila, constrói e executa o programa gerado
+    help     Mostra esta mensagem
+
+Opções de build e run:
+    -o, --out-dir <dir>     Diretório do crate gerado (padrão: dist/rust no build
+                            e um diretório de cache temporário no run)
+        --name <nome>       Nome do crate gerado (padrão: generated-code)
+        --edition <ano>     Edição do Rust usada no crate (padrão: 2021)
+        --color <quando>    Colore os diagnósticos: auto, always ou never
+                            (padrão: auto)
+    -h, --help              Mostra esta mensagem
+
+Argumentos após `--` no comando run são repassados ao programa gerado.";
+
+pub const DEFAULT_OUTPUT_DIR: &str = "dist/rust";
+
+const EDITIONS: [&str; 4] = ["2015", "2018", "2021", "2024"];
+
+#[derive(Debug)]
+pub enum Command {
+    Build(BuildOptions),
+    Run(RunOptions),
+    Help,
+}
+
+#[derive(Debug)]
+pub struct BuildOptions {
+    pub inputs: Vec<PathBuf>,
+    // `None` quando o usuário não informou `-o`; cada comando escolhe o padrão
+    pub output_dir: Option<PathBuf>,
+    pub crate_name: String,
+    pub edition: String,
+    pub color: ColorChoice,
+}
+
+#[derive(Debug)]
+pub struct RunOptions {
+    pub build: BuildOptions,
+    pub program_args: Vec<String>,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        BuildOptions {
+            inputs: Vec::new(),
+            output_dir: None,
+            crate_name: "generated-code".to_string(),
+            edition: "2021".to_string(),
+            color: ColorChoice::Auto,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("nenhum comando informado")]
+    MissingCommand,
+    #[error("comando desconhecido `{0}`")]
+    UnknownCommand(String),
+    #[error("opção desconhecida `{0}`")]
+    UnknownOption(String),
+    #[error("a opção `{0}` exige um valor")]
+    MissingValue(String),
+    #[error("nenhum arquivo de entrada informado")]
+    MissingInput,
+    #[error("nome de crate inválido `{0}`")]
+    InvalidCrateName(String),
+    #[error("edição inválida `{0}` (use 2015, 2018, 2021 ou 2024)")]
+    InvalidEdition(String),
+    #[error("valor inválido `{0}` para `--color` (use auto, always ou never)")]
+    InvalidColor(String),
+}
+
+pub fn parse_args<I>(args: I) -> Result<Command, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let args: Vec<String> = args.into_iter().collect();
+
+    let mut own_args = args.iter().take_while(|arg| *arg != "--");
+    if own_args.any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(Command::Help);
+    }
+
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("build") => parse_build_options(args).map(Command::Build),
+        Some("run") => {
+            let args: Vec<String> = args.collect();
+            // Tudo o que vem depois de `--` pertence ao programa gerado
+            let (own, program_args) = match args.iter().position(|arg| arg == "--") {
+                Some(index) => (args[..index].to_vec(), args[index + 1..].to_vec()),
+                None => (args, Vec::new()),
+            };
+            let build = parse_build_options(own.into_iter())?;
+            Ok(Command::Run(RunOptions {
+                build,
+                program_args,
+            }))
+        }
+        Some("help") => Ok(Command::Help),
+        Some(other) => Err(CliError::UnknownCommand(other.to_string())),
+        None => Err(CliError::MissingCommand),
+    }
+}
+
+fn parse_build_options(mut args: impl Iterator<Item = String>) -> Result<BuildOptions, CliError> {
+    let mut options = BuildOptions::default();
+
+    while let Some(arg) = args.next() {
+        // Aceita tanto `--opcao valor` quanto `--opcao=valor`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+
+        match flag.as_str() {
+            "-o" | "--out-dir" => {
+                options.output_dir = Some(PathBuf::from(option_value(&flag, inline_value, &mut args)?));
+            }
+            "--name" => {
+                let name = option_value(&flag, inline_value, &mut args)?;
+                if !is_valid_crate_name(&name) {
+                    return Err(CliError::InvalidCrateName(name));
+                }
+                options.crate_name = name;
+            }
+            "--edition" => {
+                let edition = option_value(&flag, inline_value, &mut args)?;
+                if !EDITIONS.contains(&edition.as_str()) {
+                    return Err(CliError::InvalidEdition(edition));
+                }
+                options.edition = edition;
+            }
+            "--color" => {
+                let value = option_value(&flag, inline_value, &mut args)?;
+                options.color = ColorChoice::parse(&value).ok_or(CliError::InvalidColor(value))?;
+            }
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(CliError::UnknownOption(flag));
+            }
+            _ => options.inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    if options.inputs.is_empty() {
+        return Err(CliError::MissingInput);
+    }
+
+    Ok(options)
+}
+
+fn option_value(
+    flag: &str,
+    inline_value: Option<String>,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, CliError> {
+    inline_value
+        .or_else(|| args.next())
+        .ok_or_else(|| CliError::MissingValue(flag.to_string()))
+}
+
+fn is_valid_crate_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
@@ -0,0 +1,290 @@
+use logos::Span;
+use std::io::{self, IsTerminal};
+use std::{env, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "erro"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    // O rótulo principal é sublinhado com `^`; os secundários com `-`
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Diagnostic::new(Severity::Error, message)
+    }
+
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: true,
+        });
+        self
+    }
+
+    pub fn with_secondary_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+// Implementado pelos erros de cada etapa do compilador (lexer, parser, ...)
+pub trait ToDiagnostic {
+    fn to_diagnostic(&self) -> Diagnostic;
+}
+
+pub struct SourceFile {
+    name: String,
+    text: String,
+    // Deslocamento em bytes do início de cada linha
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        SourceFile {
+            name: name.into(),
+            text,
+            line_starts,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // Linha e coluna (ambas a partir de 1) de um deslocamento em bytes
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.text.len());
+        let line = self.line_index(offset);
+        let column = self.text[self.line_starts[line]..offset].chars().count();
+        (line + 1, column + 1)
+    }
+
+    fn line_index(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        }
+    }
+
+    // Texto da linha (a partir de 1), sem a quebra de linha
+    fn line_text(&self, line: usize) -> &str {
+        let start = self.line_starts[line - 1];
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.text.len(), |next| next - 1);
+        self.text[start..end].trim_end_matches('\r')
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+
+const TAB_WIDTH: usize = 4;
+
+// Formata diagnósticos no estilo do rustc, com o trecho do código-fonte
+// e os rótulos sublinhados.
+pub struct Emitter {
+    color: bool,
+}
+
+impl Emitter {
+    pub fn new(choice: ColorChoice) -> Self {
+        let color = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal(),
+        };
+        Emitter { color }
+    }
+
+    pub fn emit(&self, diagnostic: &Diagnostic, file: Option<&SourceFile>) {
+        eprint!("{}", self.render(diagnostic, file));
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic, file: Option<&SourceFile>) -> String {
+        let severity_color = match diagnostic.severity {
+            Severity::Error => RED,
+        };
+
+        let mut out = format!(
+            "{}{}",
+            self.paint(severity_color, &diagnostic.severity.to_string()),
+            self.paint(BOLD, &format!(": {}", diagnostic.message))
+        );
+        out.push('\n');
+
+        let file = match file {
+            Some(file) if !diagnostic.labels.is_empty() => file,
+            _ => {
+                for note in &diagnostic.notes {
+                    out.push_str(&format!("{} nota: {}\n", self.paint(BLUE, "="), note));
+                }
+                return out;
+            }
+        };
+
+        let mut labels: Vec<&Label> = diagnostic.labels.iter().collect();
+        labels.sort_by_key(|label| (label.span.start, !label.primary));
+
+        let primary = labels
+            .iter()
+            .find(|label| label.primary)
+            .unwrap_or(&labels[0]);
+        let (line, column) = file.line_col(primary.span.start);
+        let last_line = labels
+            .iter()
+            .map(|label| file.line_col(label.span.start).0)
+            .max()
+            .unwrap_or(line);
+        let gutter = " ".repeat(last_line.to_string().len());
+
+        out.push_str(&format!(
+            "{}{} {}:{}:{}\n",
+            gutter,
+            self.paint(BLUE, "-->"),
+            file.name(),
+            line,
+            column
+        ));
+        out.push_str(&format!("{} {}\n", gutter, self.paint(BLUE, "|")));
+
+        let mut previous_line = None;
+        for label in labels {
+            let (line, _) = file.line_col(label.span.start);
+            let text = file.line_text(line);
+
+            if previous_line != Some(line) {
+                if previous_line.is_some_and(|previous| line > previous + 1) {
+                    out.push_str(&format!("{}\n", self.paint(BLUE, "...")));
+                }
+                out.push_str(&format!(
+                    "{} {} {}\n",
+                    self.paint(BLUE, &format!("{:>width$}", line, width = gutter.len())),
+                    self.paint(BLUE, "|"),
+                    expand_tabs(text)
+                ));
+                previous_line = Some(line);
+            }
+
+            // Spans que atravessam linhas são sublinhados até o fim da primeira
+            let line_start = file.line_starts[line - 1];
+            let line_end = line_start + text.len();
+            let start = display_width(&file.text[line_start..label.span.start]);
+            let end = display_width(
+                &file.text[line_start..label.span.end.clamp(label.span.start, line_end)],
+            );
+            let marker = if label.primary { "^" } else { "-" };
+            let color = if label.primary { severity_color } else { BLUE };
+
+            out.push_str(&format!(
+                "{} {} {}{}\n",
+                gutter,
+                self.paint(BLUE, "|"),
+                " ".repeat(start),
+                self.paint(
+                    color,
+                    format!("{} {}", marker.repeat((end - start).max(1)), label.message).trim_end()
+                )
+            ));
+        }
+
+        out.push_str(&format!("{} {}\n", gutter, self.paint(BLUE, "|")));
+        for note in &diagnostic.notes {
+            out.push_str(&format!("{} {} nota: {}\n", gutter, self.paint(BLUE, "="), note));
+        }
+        out.push('\n');
+
+        out
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
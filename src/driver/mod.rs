@@ -0,0 +1,255 @@
+use crate::ast::Stmt;
+use crate::cli::{BuildOptions, RunOptions, DEFAULT_OUTPUT_DIR};
+use crate::diagnostics::{Emitter, SourceFile, ToDiagnostic};
+use crate::generator::CodeGenerator;
+use crate::lexer::{LexError, Lexer};
+use crate::parser::Parser;
+use crate::resolver::Resolver;
+use crate::typeck::TypeChecker;
+use logos::Span;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DriverError {
+    #[error("não foi possível ler `{path}`: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    #[error("não foi possível gravar o crate em `{path}`: {source}")]
+    Write { path: PathBuf, source: io::Error },
+    #[error("a compilação falhou com {0} erro(s)")]
+    Compilation(usize),
+    #[error("não foi possível executar `{program}`: {source}")]
+    Spawn { program: String, source: io::Error },
+    #[error("o código Rust gerado em `{}` não compilou:\n{output}", path.display())]
+    GeneratedCode { path: PathBuf, output: String },
+}
+
+impl DriverError {
+    // Código de saída do processo para cada tipo de falha
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            DriverError::Compilation(_) => 1,
+            DriverError::Read { .. } | DriverError::Write { .. } => 3,
+            DriverError::Spawn { .. } | DriverError::GeneratedCode { .. } => 4,
+        }
+    }
+}
+
+// Gera o crate e devolve o diretório onde ele foi escrito
+pub fn build(options: &BuildOptions) -> Result<PathBuf, DriverError> {
+    let output_dir = options
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_DIR));
+
+    generate(options, &output_dir)?;
+    Ok(output_dir)
+}
+
+// Gera o crate, roda `cargo build` e executa o binário resultante,
+// devolvendo o código de saída do programa.
+pub fn run(options: &RunOptions) -> Result<u8, DriverError> {
+    let output_dir = match &options.build.output_dir {
+        Some(dir) => dir.clone(),
+        None => cache_dir(&options.build)?,
+    };
+
+    generate(&options.build, &output_dir)?;
+
+    // O diretório `target` é passado explicitamente para que o executável
+    // esteja onde esperamos mesmo com `CARGO_TARGET_DIR` definido
+    let target_dir = output_dir.join("target");
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let build_output = Command::new(&cargo)
+        .arg("build")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(output_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|source| DriverError::Spawn {
+            program: cargo.clone(),
+            source,
+        })?;
+
+    if !build_output.status.success() {
+        return Err(DriverError::GeneratedCode {
+            path: output_dir.join("src/main.rs"),
+            output: String::from_utf8_lossy(&build_output.stderr).into_owned(),
+        });
+    }
+
+    let binary = target_dir
+        .join("debug")
+        .join(format!("{}{}", options.build.crate_name, env::consts::EXE_SUFFIX));
+
+    // stdin, stdout e stderr são herdados para que a saída apareça em tempo real
+    let status = Command::new(&binary)
+        .args(&options.program_args)
+        .status()
+        .map_err(|source| DriverError::Spawn {
+            program: binary.display().to_string(),
+            source,
+        })?;
+
+    Ok(exit_status_code(status))
+}
+
+fn generate(options: &BuildOptions, output_dir: &Path) -> Result<(), DriverError> {
+    let emitter = Emitter::new(options.color);
+    let statements = compile_files(&options.inputs, &emitter)?;
+
+    CodeGenerator::new(output_dir)
+        .with_crate_name(&options.crate_name)
+        .with_edition(&options.edition)
+        .generate(statements)
+        .map_err(|source| DriverError::Write {
+            path: output_dir.to_path_buf(),
+            source,
+        })
+}
+
+// Diretório reaproveitado entre execuções dos mesmos arquivos, para que o
+// cargo possa compilar de forma incremental.
+fn cache_dir(options: &BuildOptions) -> Result<PathBuf, DriverError> {
+    let mut hasher = DefaultHasher::new();
+    for input in &options.inputs {
+        let path = fs::canonicalize(input).map_err(|source| DriverError::Read {
+            path: input.clone(),
+            source,
+        })?;
+        path.hash(&mut hasher);
+    }
+    options.crate_name.hash(&mut hasher);
+    options.edition.hash(&mut hasher);
+
+    let stem = options.inputs[0]
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(env::temp_dir()
+        .join("js-compiler")
+        .join(format!("{}-{:016x}", stem, hasher.finish())))
+}
+
+#[cfg(unix)]
+fn exit_status_code(status: std::process::ExitStatus) -> u8 {
+    use std::os::unix::process::ExitStatusExt;
+
+    // Processos encerrados por sinal seguem a convenção dos shells: 128 + sinal
+    match (status.code(), status.signal()) {
+        (Some(code), _) => code as u8,
+        (None, Some(signal)) => (128 + signal) as u8,
+        (None, None) => 1,
+    }
+}
+
+#[cfg(not(unix))]
+fn exit_status_code(status: std::process::ExitStatus) -> u8 {
+    status.code().map_or(1, |code| code as u8)
+}
+
+// Analisa todos os arquivos de entrada, na ordem em que foram informados,
+// e junta as declarações em um único programa.
+pub fn compile_files(inputs: &[PathBuf], emitter: &Emitter) -> Result<Vec<Stmt>, DriverError> {
+    let mut statements = Vec::new();
+    let mut error_count = 0;
+    let mut resolver = Resolver::new();
+    let mut checker = TypeChecker::new();
+
+    for path in inputs {
+        let source = fs::read_to_string(path).map_err(|source| DriverError::Read {
+            path: path.clone(),
+            source,
+        })?;
+        let file = SourceFile::new(path.display().to_string(), source);
+
+        match compile_source(&file, &mut resolver, &mut checker, emitter) {
+            Ok(mut parsed) => statements.append(&mut parsed),
+            Err(count) => error_count += count,
+        }
+    }
+
+    if error_count > 0 {
+        return Err(DriverError::Compilation(error_count));
+    }
+
+    Ok(statements)
+}
+
+fn compile_source(
+    file: &SourceFile,
+    resolver: &mut Resolver,
+    checker: &mut TypeChecker,
+    emitter: &Emitter,
+) -> Result<Vec<Stmt>, usize> {
+    let lexer = Lexer::new(file.text());
+    let lex_errors = lexer.get_errors().to_vec();
+    let tokens = lexer.get_tokens();
+    let token_spans: Vec<_> = tokens.iter().map(|(_, span)| span.clone()).collect();
+
+    // Os tokens reconhecidos são analisados mesmo com erros léxicos, mas os
+    // erros de sintaxe logo depois de um token descartado são só cascata
+    let mut parser = Parser::new(tokens);
+    let (mut statements, parse_errors) = parser.parse_partial();
+    if !lex_errors.is_empty() || !parse_errors.is_empty() {
+        let mut diagnostics: Vec<_> = lex_errors
+            .iter()
+            .map(|error| (error.span.start, error.to_diagnostic()))
+            .chain(
+                parse_errors
+                    .iter()
+                    .filter(|error| !follows_lex_error(error.span(), &lex_errors, &token_spans))
+                    .map(|error| (error.span().start, error.to_diagnostic())),
+            )
+            .collect();
+        diagnostics.sort_by_key(|(start, _)| *start);
+        for (_, diagnostic) in &diagnostics {
+            emitter.emit(diagnostic, Some(file));
+        }
+        return Err(diagnostics.len());
+    }
+
+    // Os tipos só são verificados quando todos os nomes foram resolvidos
+    resolver
+        .resolve(&statements)
+        .map_err(|errors| report(&errors, file, emitter))?;
+    checker
+        .check(&mut statements)
+        .map_err(|errors| report(&errors, file, emitter))?;
+    Ok(statements)
+}
+
+// Verdadeiro quando há um erro léxico entre o token do erro de sintaxe e o
+// token anterior, ou depois do último token quando o erro é no fim do arquivo
+fn follows_lex_error(span: &Span, lex_errors: &[LexError], token_spans: &[Span]) -> bool {
+    let previous_end = token_spans
+        .iter()
+        .rev()
+        .find(|token| token.end <= span.start)
+        .map_or(0, |token| token.end);
+    let next_start = token_spans
+        .iter()
+        .find(|token| token.start >= span.start)
+        .map_or(usize::MAX, |token| token.start);
+    lex_errors
+        .iter()
+        .any(|error| error.span.start >= previous_end && error.span.end <= next_start)
+}
+
+// Exibe os erros e devolve quantos foram exibidos
+fn report<E: ToDiagnostic>(errors: &[E], file: &SourceFile, emitter: &Emitter) -> usize {
+    for error in errors {
+        emitter.emit(&error.to_diagnostic(), Some(file));
+    }
+    errors.len()
+}
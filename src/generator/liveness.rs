@@ -0,0 +1,347 @@
+use crate::ast::{ArrowBody, Expr, Expression, Parameter, Statement, Stmt};
+use std::collections::HashSet;
+
+// Coleta os nomes de variáveis lidos ou escritos em um trecho da AST.
+// A análise é conservadora: um nome sombreado conta como uso do externo.
+pub fn statements_names(stmts: &[Stmt], names: &mut HashSet<String>) {
+    for stmt in stmts {
+        statement_names(stmt, names);
+    }
+}
+
+pub fn statement_names(stmt: &Stmt, names: &mut HashSet<String>) {
+    match &stmt.node {
+        Statement::Console { args: exprs, .. } => {
+            for expr in exprs {
+                expression_names(expr, names);
+            }
+        }
+        Statement::VariableDeclaration { value, .. } => {
+            if let Some(value) = value {
+                expression_names(value, names);
+            }
+        }
+        Statement::IfStatement {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            expression_names(condition, names);
+            statements_names(then_branch, names);
+            if let Some(else_branch) = else_branch {
+                statements_names(else_branch, names);
+            }
+        }
+        Statement::WhileStatement { condition, body } => {
+            expression_names(condition, names);
+            statements_names(body, names);
+        }
+        Statement::ForStatement {
+            init,
+            condition,
+            update,
+            body,
+        } => {
+            if let Some(init) = init {
+                statement_names(init, names);
+            }
+            for expr in condition.iter().chain(update) {
+                expression_names(expr, names);
+            }
+            statements_names(body, names);
+        }
+        Statement::ForOfStatement { iterable, body, .. } => {
+            expression_names(iterable, names);
+            statements_names(body, names);
+        }
+        Statement::Break | Statement::Continue => {}
+        Statement::Assignment { name, value, .. } => {
+            names.insert(name.node.clone());
+            expression_names(value, names);
+        }
+        Statement::FunctionDeclaration { body, .. } => statements_names(body, names),
+        Statement::Return(value) => {
+            if let Some(value) = value {
+                expression_names(value, names);
+            }
+        }
+        Statement::Expression(expr) => expression_names(expr, names),
+    }
+}
+
+pub fn expression_names(expr: &Expr, names: &mut HashSet<String>) {
+    match &expr.node {
+        Expression::StringLiteral(_)
+        | Expression::NumberLiteral(_)
+        | Expression::BooleanLiteral(_) => {}
+        Expression::Identifier(name) => {
+            names.insert(name.clone());
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            expression_names(left, names);
+            expression_names(right, names);
+        }
+        Expression::Assignment { name, value, .. } => {
+            names.insert(name.node.clone());
+            expression_names(value, names);
+        }
+        Expression::Update { name, .. } => {
+            names.insert(name.node.clone());
+        }
+        Expression::Conditional {
+            condition,
+            consequent,
+            alternate,
+        } => {
+            expression_names(condition, names);
+            expression_names(consequent, names);
+            expression_names(alternate, names);
+        }
+        Expression::ArrayLiteral(elements) => {
+            for element in elements {
+                expression_names(element, names);
+            }
+        }
+        Expression::Unary { operand, .. } => expression_names(operand, names),
+        Expression::Call { callee, args } => {
+            expression_names(callee, names);
+            for arg in args {
+                expression_names(arg, names);
+            }
+        }
+        Expression::ArrowFunction { body, .. } => match body {
+            ArrowBody::Expression(expr) => expression_names(expr, names),
+            ArrowBody::Block(stmts) => statements_names(stmts, names),
+        },
+    }
+}
+
+// Variáveis do escopo externo usadas por uma arrow function: nomes que
+// aparecem no corpo e não são parâmetros nem declarados dentro dele.
+pub fn captured_variables(params: &[Parameter], body: &ArrowBody) -> Vec<String> {
+    let mut used = HashSet::new();
+    let mut declared: HashSet<String> = params.iter().map(|param| param.name.node.clone()).collect();
+
+    match body {
+        ArrowBody::Expression(expr) => expression_names(expr, &mut used),
+        ArrowBody::Block(stmts) => {
+            statements_names(stmts, &mut used);
+            declared_names(stmts, &mut declared);
+        }
+    }
+
+    let mut captured: Vec<String> = used.difference(&declared).cloned().collect();
+    captured.sort();
+    captured
+}
+
+fn parameter_names(params: &[Parameter]) -> Vec<&str> {
+    params
+        .iter()
+        .map(|param| param.name.node.as_str())
+        .collect()
+}
+
+fn declared_names(stmts: &[Stmt], names: &mut HashSet<String>) {
+    for stmt in stmts {
+        match &stmt.node {
+            Statement::VariableDeclaration { name, .. }
+            | Statement::FunctionDeclaration { name, .. } => {
+                names.insert(name.node.clone());
+            }
+            Statement::IfStatement {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                declared_names(then_branch, names);
+                if let Some(else_branch) = else_branch {
+                    declared_names(else_branch, names);
+                }
+            }
+            Statement::WhileStatement { body, .. } => declared_names(body, names),
+            Statement::ForStatement { init, body, .. } => {
+                if let Some(init) = init {
+                    declared_names(std::slice::from_ref(&**init), names);
+                }
+                declared_names(body, names);
+            }
+            Statement::ForOfStatement { variable, body, .. } => {
+                names.insert(variable.node.clone());
+                declared_names(body, names);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Nomes de fora do trecho que recebem atribuição em algum ponto dele. Ao
+// contrário dos usos, aqui o sombreamento é respeitado: uma atribuição a uma
+// variável declarada em um bloco interno não torna a externa mutável.
+pub fn assigned_names(stmts: &[Stmt], names: &mut HashSet<String>) {
+    for stmt in stmts {
+        statement_assigned_names(stmt, names);
+    }
+}
+
+// Atribuições feitas em um bloco novo, sem as variáveis declaradas nele
+// diretamente nem os `locals`, como os parâmetros de uma função.
+pub fn block_assigned_names(stmts: &[Stmt], locals: &[&str], names: &mut HashSet<String>) {
+    let mut inner = HashSet::new();
+    assigned_names(stmts, &mut inner);
+    for stmt in stmts {
+        if let Statement::VariableDeclaration { name, .. }
+        | Statement::FunctionDeclaration { name, .. } = &stmt.node
+        {
+            inner.remove(&name.node);
+        }
+    }
+    for local in locals {
+        inner.remove(*local);
+    }
+    names.extend(inner);
+}
+
+pub fn statement_assigned_names(stmt: &Stmt, names: &mut HashSet<String>) {
+    match &stmt.node {
+        Statement::Assignment { name, value, .. } => {
+            names.insert(name.node.clone());
+            expression_assigned_names(value, names);
+        }
+        Statement::VariableDeclaration { value, .. } => {
+            if let Some(value) = value {
+                expression_assigned_names(value, names);
+            }
+        }
+        Statement::Console { args: exprs, .. } => {
+            for expr in exprs {
+                expression_assigned_names(expr, names);
+            }
+        }
+        Statement::IfStatement {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            expression_assigned_names(condition, names);
+            block_assigned_names(then_branch, &[], names);
+            if let Some(else_branch) = else_branch {
+                block_assigned_names(else_branch, &[], names);
+            }
+        }
+        Statement::WhileStatement { condition, body } => {
+            expression_assigned_names(condition, names);
+            block_assigned_names(body, &[], names);
+        }
+        Statement::ForStatement {
+            init,
+            condition,
+            update,
+            body,
+        } => {
+            let mut inner = HashSet::new();
+            if let Some(init) = init {
+                statement_assigned_names(init, &mut inner);
+            }
+            for expr in condition.iter().chain(update) {
+                expression_assigned_names(expr, &mut inner);
+            }
+            block_assigned_names(body, &[], &mut inner);
+            // A variável do `init` só existe dentro do laço
+            if let Some(Statement::VariableDeclaration { name, .. }) =
+                init.as_deref().map(|init| &init.node)
+            {
+                inner.remove(&name.node);
+            }
+            names.extend(inner);
+        }
+        Statement::ForOfStatement {
+            variable,
+            iterable,
+            body,
+            ..
+        } => {
+            expression_assigned_names(iterable, names);
+            block_assigned_names(body, &[&variable.node], names);
+        }
+        Statement::FunctionDeclaration { params, body, .. } => {
+            block_assigned_names(body, &parameter_names(params), names);
+        }
+        Statement::Return(Some(expr)) | Statement::Expression(expr) => {
+            expression_assigned_names(expr, names);
+        }
+        Statement::Return(None) | Statement::Break | Statement::Continue => {}
+    }
+}
+
+pub fn expression_assigned_names(expr: &Expr, names: &mut HashSet<String>) {
+    match &expr.node {
+        Expression::Assignment { name, value, .. } => {
+            names.insert(name.node.clone());
+            expression_assigned_names(value, names);
+        }
+        Expression::Update { name, .. } => {
+            names.insert(name.node.clone());
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            expression_assigned_names(left, names);
+            expression_assigned_names(right, names);
+        }
+        Expression::Conditional {
+            condition,
+            consequent,
+            alternate,
+        } => {
+            expression_assigned_names(condition, names);
+            expression_assigned_names(consequent, names);
+            expression_assigned_names(alternate, names);
+        }
+        Expression::ArrayLiteral(elements) => {
+            for element in elements {
+                expression_assigned_names(element, names);
+            }
+        }
+        Expression::Unary { operand, .. } => expression_assigned_names(operand, names),
+        Expression::Call { callee, args } => {
+            expression_assigned_names(callee, names);
+            for arg in args {
+                expression_assigned_names(arg, names);
+            }
+        }
+        Expression::ArrowFunction { params, body, .. } => {
+            let params = parameter_names(params);
+            match body {
+                ArrowBody::Expression(expr) => {
+                    let mut inner = HashSet::new();
+                    expression_assigned_names(expr, &mut inner);
+                    for param in params {
+                        inner.remove(param);
+                    }
+                    names.extend(inner);
+                }
+                ArrowBody::Block(stmts) => block_assigned_names(stmts, &params, names),
+            }
+        }
+        Expression::StringLiteral(_)
+        | Expression::NumberLiteral(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::Identifier(_) => {}
+    }
+}
+
+// Indica se o corpo tem um `continue` que se refere ao próprio laço, ou
+// seja, fora de laços aninhados.
+pub fn has_own_continue(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match &stmt.node {
+        Statement::Continue => true,
+        Statement::IfStatement {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            has_own_continue(then_branch)
+                || else_branch.as_deref().is_some_and(has_own_continue)
+        }
+        _ => false,
+    })
+}
@@ -1,197 +1,1329 @@
-use crate::ast::{BinaryOperator, Expression, Statement, Type};
+mod liveness;
+mod narrowing;
+mod runtime;
+
+use crate::ast::{
+    function_type, ArrowBody, BinaryOperator, Expr, Expression, Ident, Parameter, Statement, Stmt,
+    Type, UnaryOperator, UpdateOperator,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::PathBuf;
 
 pub struct CodeGenerator {
-    output_dir: String,
+    output_dir: PathBuf,
+    crate_name: String,
+    edition: String,
+    // Nomes usados depois da declaração sendo gerada, no bloco atual ou nos
+    // blocos que o envolvem; decide entre closures `move` ou por referência.
+    live_after: HashSet<String>,
+    // Um item por laço sendo gerado; `Some(rótulo)` quando o `continue` do
+    // laço precisa virar `break 'rótulo` para ainda executar o incremento.
+    continue_labels: Vec<Option<String>>,
+    // Um item por laço, em paralelo a `continue_labels`: `Some(rótulo)` quando
+    // o corpo está em um bloco rotulado e o `break` precisa nomear o laço.
+    break_labels: Vec<Option<String>>,
+    // Nomes que podem receber um valor não inteiro depois da declaração sendo
+    // gerada, no bloco atual; os demais `number` declarados com inteiros viram `i64`.
+    non_integral_after: HashSet<String>,
+    // Nomes que recebem atribuição depois da declaração sendo gerada, no bloco
+    // atual; decide quais variáveis são declaradas com `mut`.
+    assigned_after: HashSet<String>,
+    // Variáveis e funções visíveis, um mapa por escopo
+    scopes: Vec<HashMap<String, Binding>>,
+    // Trechos de `runtime` usados pelo código gerado até aqui
+    runtime: BTreeSet<&'static str>,
+}
+
+struct Binding {
+    // Tipo TypeScript, quando conhecido
+    ty: Option<Type>,
+    // `number` gerado como `i64`, convertido para `f64` onde é lido
+    integer: bool,
+    // Variáveis capturadas pela arrow function guardada na variável
+    captures: Vec<String>,
+}
+
+impl Binding {
+    fn new(ty: Option<Type>) -> Self {
+        Binding {
+            ty,
+            integer: false,
+            captures: Vec::new(),
+        }
+    }
+
+    fn integer() -> Self {
+        Binding {
+            ty: Some(Type::Number),
+            integer: true,
+            captures: Vec::new(),
+        }
+    }
 }
 
 impl CodeGenerator {
-    pub fn new(output_dir: &str) -> Self {
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
         CodeGenerator {
-            output_dir: output_dir.to_string(),
+            output_dir: output_dir.into(),
+            crate_name: "generated-code".to_string(),
+            edition: "2021".to_string(),
+            live_after: HashSet::new(),
+            continue_labels: Vec::new(),
+            break_labels: Vec::new(),
+            non_integral_after: HashSet::new(),
+            assigned_after: HashSet::new(),
+            scopes: vec![HashMap::new()],
+            runtime: BTreeSet::new(),
         }
     }
 
-    pub fn generate(&self, statements: Vec<Statement>) -> Result<(), std::io::Error> {
+    pub fn with_crate_name(mut self, crate_name: &str) -> Self {
+        self.crate_name = crate_name.to_string();
+        self
+    }
+
+    pub fn with_edition(mut self, edition: &str) -> Self {
+        self.edition = edition.to_string();
+        self
+    }
+
+    pub fn generate(&mut self, statements: Vec<Stmt>) -> Result<(), std::io::Error> {
         // Cria o diretório de saída se não existir
         fs::create_dir_all(&self.output_dir)?;
 
         // Cria o Cargo.toml
-        let cargo_toml = r#"[package]
-name = "generated-code"
-version = "0.1.0"
-edition = "2021"
+        let cargo_toml = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"{}\"\n\n[dependencies]\n",
+            self.crate_name, self.edition
+        );
+        fs::write(self.output_dir.join("Cargo.toml"), cargo_toml)?;
 
-[dependencies]
-"#;
-        fs::write(Path::new(&self.output_dir).join("Cargo.toml"), cargo_toml)?;
+        // Funções declaradas no topo do arquivo viram itens `fn` do Rust;
+        // o restante das declarações vai para dentro de `main`.
+        let (functions, main_body): (Vec<Stmt>, Vec<Stmt>) = statements
+            .into_iter()
+            .partition(|stmt| matches!(stmt.node, Statement::FunctionDeclaration { .. }));
 
-        let mut code = String::new();
-        code.push_str("fn main() {\n");
+        for function in &functions {
+            if let Statement::FunctionDeclaration {
+                name,
+                params,
+                return_type,
+                ..
+            } = &function.node
+            {
+                self.declare(&name.node, Binding::new(function_type(params, return_type)));
+            }
+        }
 
-        for stmt in statements {
-            code.push_str(&self.generate_statement(&stmt));
+        let mut code = String::new();
+        for function in &functions {
+            code.push_str(&self.generate_statement(function));
+            code.push('\n');
         }
 
+        code.push_str("fn main() {\n");
+        code.push_str(&self.generate_block(&main_body, &HashSet::new()));
         code.push_str("}\n");
 
+        for helper in &self.runtime {
+            code.push('\n');
+            code.push_str(helper);
+        }
+
         // Escreve o código no arquivo
-        let output_path = Path::new(&self.output_dir).join("src/main.rs");
-        fs::create_dir_all(Path::new(&self.output_dir).join("src"))?;
+        let output_path = self.output_dir.join("src/main.rs");
+        fs::create_dir_all(self.output_dir.join("src"))?;
         fs::write(output_path, code)?;
 
         Ok(())
     }
 
-    fn generate_statement(&self, stmt: &Statement) -> String {
-        match stmt {
-                    Statement::ConsoleLog(exprs) => {
-            if exprs.len() == 1 {
-                format!(
-                    "    println!(\"{{:?}}\", {});\n",
-                    self.generate_expression(&exprs[0])
-                )
-            } else {
-                // Monta a string format e lista de argumentos
-                let mut format_string = String::new();
-                let mut expr_list = Vec::new();
+    // Gera as declarações de um bloco; `live_after` são os nomes usados
+    // depois do bloco terminar.
+    fn generate_block(&mut self, stmts: &[Stmt], live_after: &HashSet<String>) -> String {
+        // Nomes usados a partir de cada declaração até o fim do bloco. Usar
+        // uma closure também usa as variáveis que ela pega emprestadas.
+        let mut closures = HashMap::new();
+        for stmt in stmts {
+            if let Statement::VariableDeclaration {
+                name,
+                value: Some(value),
+                ..
+            } = &stmt.node
+            {
+                if let Expression::ArrowFunction { params, body, .. } = &value.node {
+                    closures.insert(
+                        name.node.clone(),
+                        liveness::captured_variables(params, body),
+                    );
+                }
+            }
+        }
+        let mut suffixes = vec![live_after.clone()];
+        for stmt in stmts.iter().rev() {
+            let mut names = suffixes.last().cloned().unwrap_or_default();
+            liveness::statement_names(stmt, &mut names);
+            self.add_captures(&mut names, &closures);
+            suffixes.push(names);
+        }
+        suffixes.reverse();
+
+        // Mesma ideia para as atribuições que impedem uma variável de ser inteira
+        let mut writes = vec![HashSet::new()];
+        for stmt in stmts.iter().rev() {
+            let mut names = writes.last().cloned().unwrap_or_default();
+            narrowing::statement_non_integral_writes(stmt, &mut names);
+            writes.push(names);
+        }
+        writes.reverse();
+
+        let mut assignments = vec![HashSet::new()];
+        for stmt in stmts.iter().rev() {
+            let mut names = assignments.last().cloned().unwrap_or_default();
+            liveness::statement_assigned_names(stmt, &mut names);
+            assignments.push(names);
+        }
+        assignments.reverse();
+
+        let saved = std::mem::take(&mut self.live_after);
+        let saved_writes = std::mem::take(&mut self.non_integral_after);
+        let saved_assignments = std::mem::take(&mut self.assigned_after);
+        self.scopes.push(HashMap::new());
+        let mut code = String::new();
+        for (index, stmt) in stmts.iter().enumerate() {
+            self.live_after = suffixes[index + 1].clone();
+            self.non_integral_after = writes[index + 1].clone();
+            self.assigned_after = assignments[index + 1].clone();
+            code.push_str(&self.generate_statement(stmt));
+        }
+        self.scopes.pop();
+        self.assigned_after = saved_assignments;
+        self.non_integral_after = saved_writes;
+        self.live_after = saved;
+        code
+    }
+
+    // Acrescenta a `names` as variáveis capturadas pelas closures que estão
+    // nele, sejam do bloco atual (`closures`) ou de escopos externos
+    fn add_captures(&self, names: &mut HashSet<String>, closures: &HashMap<String, Vec<String>>) {
+        let mut pending: Vec<String> = names.iter().cloned().collect();
+        while let Some(name) = pending.pop() {
+            let captures = match closures.get(&name) {
+                Some(captures) => captures,
+                None => match self.binding(&name) {
+                    Some(binding) => &binding.captures,
+                    None => continue,
+                },
+            };
+            for capture in captures {
+                if names.insert(capture.clone()) {
+                    pending.push(capture.clone());
+                }
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str, binding: Binding) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), binding);
+        }
+    }
+
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Type> {
+        self.binding(name)?.ty.as_ref()
+    }
+
+    fn is_integer(&self, name: &str) -> bool {
+        self.binding(name).is_some_and(|binding| binding.integer)
+    }
+
+    // Texto de um argumento do `console.log`, pelo trait `Inspect` do runtime
+    fn generate_display(&mut self, expr: &Expr) -> String {
+        self.runtime.insert(runtime::INSPECT);
+        format!("Inspect::display({})", self.generate_reference(expr))
+    }
+
+    // Referência ao valor de uma expressão, para os métodos do runtime
+    fn generate_reference(&mut self, expr: &Expr) -> String {
+        // Um array sem elementos de tipo conhecido, como `[]`, não deixa o
+        // Rust inferir o tipo; o tipo dos elementos não aparece na saída
+        if is_untyped_array(expr) {
+            return format!("&{}", generate_untyped_array(expr));
+        }
+        let code = self.generate_expression(expr);
+        match &expr.node {
+            Expression::Identifier(name) if !self.is_integer(name) => format!("&{}", code),
+            Expression::NumberLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::ArrayLiteral(_)
+            | Expression::Call { .. } => format!("&{}", code),
+            _ => format!("&({})", code),
+        }
+    }
+
+    // Substitui os especificadores como `%s` e `%d` de uma string literal no
+    // primeiro argumento, seguindo o `util.format` do Node. Devolve o índice
+    // do primeiro argumento que não foi usado na substituição.
+    fn generate_specifiers(
+        &mut self,
+        args: &[Expr],
+        format_string: &mut String,
+        values: &mut Vec<String>,
+    ) -> usize {
+        let Some(Expression::StringLiteral(first)) = args.first().map(|arg| &arg.node) else {
+            return 0;
+        };
+        if args.len() == 1 {
+            return 0;
+        }
+
+        let chars: Vec<char> = first.chars().collect();
+        let text =
+            |start: usize, end: usize| format_text(&chars[start..end].iter().collect::<String>());
+        let mut used = 0;
+        let mut last = 0;
+        let mut i = 0;
+        while i + 1 < chars.len() {
+            if chars[i] == '%' {
+                i += 1;
+                let specifier = chars[i];
+                if specifier == '%' {
+                    // `%%` vira `%`
+                    format_string.push_str(&text(last, i));
+                    last = i + 1;
+                } else if used + 1 < args.len() && "sjdoOifc".contains(specifier) {
+                    used += 1;
+                    format_string.push_str(&text(last, i - 1));
+                    self.generate_specifier(specifier, &args[used], format_string, values);
+                    last = i + 1;
+                }
+            }
+            i += 1;
+        }
+
+        // Sem nenhuma substituição, a string é exibida como os demais argumentos
+        if last == 0 {
+            return 0;
+        }
+        format_string.push_str(&text(last, chars.len()));
+        used + 1
+    }
+
+    fn generate_specifier(
+        &mut self,
+        specifier: char,
+        arg: &Expr,
+        format_string: &mut String,
+        values: &mut Vec<String>,
+    ) {
+        // Funções não têm o código-fonte nem um valor numérico no Rust
+        if let Expression::Identifier(name) = &arg.node {
+            if matches!(self.lookup(name), Some(Type::Function { .. })) {
+                let text = match specifier {
+                    's' | 'o' | 'O' => format!("[Function: {}]", name),
+                    'd' | 'i' | 'f' => "NaN".to_string(),
+                    'j' => "undefined".to_string(),
+                    _ => String::new(),
+                };
+                format_string.push_str(&text);
+                return;
+            }
+        }
+
+        self.runtime.insert(runtime::INSPECT);
+        if specifier != 'o' && specifier != 'O' {
+            self.runtime.insert(runtime::FORMAT);
+        }
+        let value = match specifier {
+            // `%c` só consome o argumento
+            'c' => match &arg.node {
+                Expression::Identifier(_)
+                | Expression::StringLiteral(_)
+                | Expression::NumberLiteral(_)
+                | Expression::BooleanLiteral(_) => return,
+                _ => format!("{{ let _ = {}; \"\" }}", self.generate_expression(arg)),
+            },
+            's' => format!("Format::format_s({})", self.generate_reference(arg)),
+            'j' => format!("Format::to_json({})", self.generate_reference(arg)),
+            'd' => format!(
+                "Inspect::display(&Format::to_number({}))",
+                self.generate_reference(arg)
+            ),
+            'i' => format!(
+                "Inspect::display(&parse_int(&Format::to_js_string({})))",
+                self.generate_reference(arg)
+            ),
+            'f' => format!(
+                "Inspect::display(&parse_float(&Format::to_js_string({})))",
+                self.generate_reference(arg)
+            ),
+            _ => format!("Inspect::inspect({}, 0, 0)", self.generate_reference(arg)),
+        };
+        format_string.push_str("{}");
+        values.push(value);
+    }
+
+    // Gera um bloco em um escopo novo em que `bindings` já estão declarados
+    fn generate_scoped_block(
+        &mut self,
+        bindings: Vec<(&str, Binding)>,
+        stmts: &[Stmt],
+        live_after: &HashSet<String>,
+    ) -> String {
+        self.scopes.push(HashMap::new());
+        for (name, binding) in bindings {
+            self.declare(name, binding);
+        }
+        let code = self.generate_block(stmts, live_after);
+        self.scopes.pop();
+        code
+    }
+
+    // Tipo TypeScript de uma expressão, quando dá para descobrir sem inferência
+    fn type_of(&self, expr: &Expr) -> Option<Type> {
+        match &expr.node {
+            Expression::StringLiteral(_) => Some(Type::String),
+            Expression::NumberLiteral(_) => Some(Type::Number),
+            Expression::BooleanLiteral(_) => Some(Type::Boolean),
+            Expression::Identifier(name) => self.lookup(name).cloned(),
+            Expression::BinaryOp { left, op, right } => match op {
+                BinaryOperator::Add => {
+                    let is_string = |expr| self.type_of(expr) == Some(Type::String);
+                    if is_string(left) || is_string(right) {
+                        Some(Type::String)
+                    } else {
+                        Some(Type::Number)
+                    }
+                }
+                // `a || b` tem o valor de um dos operandos
+                BinaryOperator::And | BinaryOperator::Or => {
+                    let left_type = self.type_of(left);
+                    if left_type.is_some() && left_type == self.type_of(right) {
+                        left_type
+                    } else {
+                        Some(Type::Boolean)
+                    }
+                }
+                _ if op.is_comparison() => Some(Type::Boolean),
+                _ => Some(Type::Number),
+            },
+            Expression::Unary { op, .. } => match op {
+                UnaryOperator::Not => Some(Type::Boolean),
+                UnaryOperator::Negate | UnaryOperator::BitNot => Some(Type::Number),
+            },
+            Expression::Assignment { name, value, .. } => {
+                self.lookup(&name.node).cloned().or_else(|| self.type_of(value))
+            }
+            Expression::Update { .. } => Some(Type::Number),
+            Expression::Conditional {
+                consequent,
+                alternate,
+                ..
+            } => self.type_of(consequent).or_else(|| self.type_of(alternate)),
+            Expression::ArrayLiteral(elements) => {
+                let element = self.type_of(elements.first()?)?;
+                Some(Type::Array(Box::new(element)))
+            }
+            Expression::Call { callee, .. } => match self.type_of(callee)? {
+                Type::Function { return_type, .. } => Some(*return_type),
+                _ => None,
+            },
+            Expression::ArrowFunction {
+                params,
+                return_type,
+                ..
+            } => function_type(params, return_type),
+        }
+    }
 
-                for expr in exprs {
-                    match expr {
+    fn generate_statement(&mut self, stmt: &Stmt) -> String {
+        match &stmt.node {
+            Statement::Console { method, args } => {
+                // Strings literais vão direto para a string format; os demais
+                // valores são exibidos como no Node, separados por espaços.
+                let mut format_string = String::new();
+                let mut values = Vec::new();
+                let first = self.generate_specifiers(args, &mut format_string, &mut values);
+                for (index, arg) in args.iter().enumerate().skip(first) {
+                    if index > 0 {
+                        format_string.push(' ');
+                    }
+                    match &arg.node {
                         Expression::StringLiteral(s) => {
-                            format_string.push_str(&s.trim_matches('"'));
+                            format_string.push_str(&format_text(s));
+                        }
+                        Expression::Identifier(name)
+                            if matches!(self.lookup(name), Some(Type::Function { .. })) =>
+                        {
+                            format_string.push_str(&format!("[Function: {}]", name));
                         }
                         _ => {
-                            format_string.push_str("{:?} ");
-                            expr_list.push(self.generate_expression(expr));
+                            format_string.push_str("{}");
+                            values.push(self.generate_display(arg));
                         }
                     }
                 }
 
-                format_string = format_string.trim_end().to_string();
-
-                format!(
-                    "    println!(\"{}\", {});\n",
-                    format_string,
-                    expr_list.join(", ")
-                )
+                let macro_name = if method.is_stderr() { "eprintln" } else { "println" };
+                if values.is_empty() {
+                    format!("    {}!(\"{}\");\n", macro_name, format_string)
+                } else {
+                    format!(
+                        "    {}!(\"{}\", {});\n",
+                        macro_name,
+                        format_string,
+                        values.join(", ")
+                    )
+                }
             }
-        }
             Statement::VariableDeclaration {
                 name,
                 type_annotation,
                 value,
-            } => {
-                let type_str = match type_annotation {
-                    Type::String => "String".to_string(),
-                    Type::Number => "i32".to_string(),
-                    Type::Boolean => "bool".to_string(),
-                    Type::Array(inner) => format!("Vec<{}>", match **inner {
-                    Type::String => "String",
-                    Type::Number => "i32",
-                    Type::Boolean => "bool",
-        _ => "Unknown", // só para segurança
-    }),
-                };
-
-                let value_str = if let Some(expr) = value {
-                    format!(" = {}", self.generate_expression(expr))
-                } else {
-                    String::new()
-                };
-
-                let mut_str = if name == "contador" { "mut " } else { "" };
-
-                format!("    let {}{}: {}{};\n", mut_str, name, type_str, value_str)
-            }
+                ..
+            } => self.generate_variable_declaration(name, type_annotation, value),
 
             Statement::IfStatement {
                 condition,
                 then_branch,
                 else_branch,
             } => {
-                let mut code = format!("    if {} {{\n", self.generate_expression(condition));
+                let live_after = self.live_after.clone();
+                let mut code = format!("    if {} {{\n", self.generate_condition(condition));
 
-                for stmt in then_branch {
-                    code.push_str(&self.generate_statement(stmt));
-                }
+                code.push_str(&self.generate_block(then_branch, &live_after));
 
                 code.push_str("    }");
 
                 if let Some(else_statements) = else_branch {
                     code.push_str(" else {\n");
-                    for stmt in else_statements {
-                        code.push_str(&self.generate_statement(stmt));
-                    }
+                    code.push_str(&self.generate_block(else_statements, &live_after));
                     code.push_str("    }");
                 }
 
-                code.push_str("\n");
+                code.push('\n');
                 code
             }
             Statement::WhileStatement { condition, body } => {
-                let mut code = format!("    while {} {{\n", self.generate_expression(condition));
-                for stmt in body {
-                    code.push_str(&self.generate_statement(stmt));
+                // Tudo que o laço usa continua vivo na próxima iteração
+                let mut live_after = self.live_after.clone();
+                liveness::statement_names(stmt, &mut live_after);
+
+                let mut code = format!("    while {} {{\n", self.generate_condition(condition));
+                self.continue_labels.push(None);
+                self.break_labels.push(None);
+                code.push_str(&self.generate_block(body, &live_after));
+                self.break_labels.pop();
+                self.continue_labels.pop();
+                code.push_str("    }\n");
+                code
+            }
+            Statement::ForStatement {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                let mut live_after = self.live_after.clone();
+                liveness::statement_names(stmt, &mut live_after);
+
+                // O contador começa em um inteiro e só é incrementado, então é
+                // um `i64`; como `i < b` equivale a `i < ceil(b)`, o limite também.
+                if let Some((name, start, end)) = simple_counter(init, condition, update, body) {
+                    let end_code = match self.generate_integer(end) {
+                        Some(end) => end,
+                        None => match &end.node {
+                            Expression::Identifier(_) => {
+                                format!("{}.ceil() as i64", self.generate_expression(end))
+                            }
+                            _ => format!("({}).ceil() as i64", self.generate_expression(end)),
+                        },
+                    };
+                    let mut code = format!(
+                        "    for {} in {}..{} {{\n",
+                        name,
+                        integer_literal(start),
+                        end_code
+                    );
+                    self.continue_labels.push(None);
+                    self.break_labels.push(None);
+                    code.push_str(&self.generate_scoped_block(
+                        vec![(name, Binding::integer())],
+                        body,
+                        &live_after,
+                    ));
+                    self.break_labels.pop();
+                    self.continue_labels.pop();
+                    code.push_str("    }\n");
+                    return code;
                 }
+
+                // Caso geral: `{ init; while cond { corpo; update; } }`. Se o corpo
+                // tiver `continue`, ele fica em um bloco rotulado para que o
+                // `continue` salte só o restante do corpo e o update ainda rode.
+                let mut code = String::from("    {\n");
+                self.scopes.push(HashMap::new());
+                if let Some(init) = init {
+                    // A variável do `init` só é alterada pelo próprio laço
+                    let mut writes = HashSet::new();
+                    for expr in condition.iter().chain(update) {
+                        narrowing::expression_non_integral_writes(expr, &mut writes);
+                    }
+                    narrowing::non_integral_writes(body, &mut writes);
+                    let mut assigned = HashSet::new();
+                    for expr in condition.iter().chain(update) {
+                        liveness::expression_assigned_names(expr, &mut assigned);
+                    }
+                    liveness::block_assigned_names(body, &[], &mut assigned);
+                    let saved_writes = std::mem::replace(&mut self.non_integral_after, writes);
+                    let saved_assignments = std::mem::replace(&mut self.assigned_after, assigned);
+
+                    code.push_str(&self.generate_statement(init));
+                    self.assigned_after = saved_assignments;
+                    self.non_integral_after = saved_writes;
+                }
+
+                // Dentro do bloco rotulado o Rust não aceita `break` sem rótulo,
+                // então o próprio laço também recebe um rótulo.
+                let index = self.continue_labels.len();
+                let label = (update.is_some() && liveness::has_own_continue(body))
+                    .then(|| format!("'corpo{}", index));
+                let loop_label = label.as_ref().map(|_| format!("'laco{}", index));
+                let loop_prefix = loop_label
+                    .as_ref()
+                    .map_or(String::new(), |label| format!("{}: ", label));
+
+                match condition {
+                    Some(condition) => code.push_str(&format!(
+                        "    {}while {} {{\n",
+                        loop_prefix,
+                        self.generate_condition(condition)
+                    )),
+                    None => code.push_str(&format!("    {}loop {{\n", loop_prefix)),
+                }
+
+                self.continue_labels.push(label.clone());
+                self.break_labels.push(loop_label);
+                let body_code = self.generate_block(body, &live_after);
+                self.break_labels.pop();
+                self.continue_labels.pop();
+
+                match label {
+                    Some(label) => {
+                        code.push_str(&format!("    {}: {{\n", label));
+                        code.push_str(&body_code);
+                        code.push_str("    }\n");
+                    }
+                    None => code.push_str(&body_code),
+                }
+                if let Some(update) = update {
+                    code.push_str(&format!("    {};\n", self.generate_effect(update)));
+                }
+                self.scopes.pop();
+                code.push_str("    }\n    }\n");
+                code
+            }
+            Statement::ForOfStatement {
+                variable,
+                iterable,
+                body,
+                ..
+            } => {
+                let mut live_after = self.live_after.clone();
+                liveness::statement_names(stmt, &mut live_after);
+
+                let element = match self.type_of(iterable) {
+                    Some(Type::Array(element)) => Some(*element),
+                    _ => None,
+                };
+
+                // Um array que não é mais usado depois do laço (nem dentro dele)
+                // é consumido; caso contrário, os elementos são copiados ou
+                // clonados, para que a variável do laço seja um valor como no
+                // array consumido.
+                let mut body_names = HashSet::new();
+                liveness::statements_names(body, &mut body_names);
+                let consumed = match &iterable.node {
+                    Expression::Identifier(name) => {
+                        !self.live_after.contains(name) && !body_names.contains(name)
+                    }
+                    _ => true,
+                };
+
+                let iterable_code = self.generate_expression(iterable);
+                let iterator = if consumed {
+                    iterable_code
+                } else if matches!(element, Some(Type::Number | Type::Boolean)) {
+                    format!("{}.iter().copied()", iterable_code)
+                } else {
+                    format!("{}.iter().cloned()", iterable_code)
+                };
+
+                let mut assigned = HashSet::new();
+                liveness::block_assigned_names(body, &[], &mut assigned);
+                let mut_str = if assigned.contains(&variable.node) {
+                    "mut "
+                } else {
+                    ""
+                };
+
+                let mut code = format!("    for {}{} in {} {{\n", mut_str, variable.node, iterator);
+                self.continue_labels.push(None);
+                self.break_labels.push(None);
+                code.push_str(&self.generate_scoped_block(
+                    vec![(&variable.node, Binding::new(element))],
+                    body,
+                    &live_after,
+                ));
+                self.break_labels.pop();
+                self.continue_labels.pop();
                 code.push_str("    }\n");
                 code
             }
-            Statement::Assignment { name, value } => {
-                format!("    {} = {};\n", name, self.generate_expression(value))
+            Statement::Break => match self.break_labels.last() {
+                Some(Some(label)) => format!("    break {};\n", label),
+                _ => "    break;\n".to_string(),
+            },
+            Statement::Continue => match self.continue_labels.last() {
+                Some(Some(label)) => format!("    break {};\n", label),
+                _ => "    continue;\n".to_string(),
+            },
+            Statement::Assignment { name, op, value } => {
+                format!("    {};\n", self.generate_assignment(name, op, value))
+            }
+            Statement::FunctionDeclaration {
+                name,
+                params,
+                return_type,
+                body,
+            } => {
+                let return_str = match return_type {
+                    None | Some(Type::Void) => String::new(),
+                    Some(ty) => format!(" -> {}", rust_type(ty)),
+                };
+
+                let mut assigned = HashSet::new();
+                liveness::block_assigned_names(body, &[], &mut assigned);
+                let mut code = format!(
+                    "fn {}({}){} {{\n",
+                    name.node,
+                    generate_parameters(params, &assigned),
+                    return_str
+                );
+                let bindings = params
+                    .iter()
+                    .map(|param| {
+                        let binding = Binding::new(param.type_annotation.clone());
+                        (param.name.node.as_str(), binding)
+                    })
+                    .collect();
+                code.push_str(&self.generate_scoped_block(bindings, body, &HashSet::new()));
+                code.push_str("}\n");
+                code
+            }
+            Statement::Return(value) => match value {
+                // Uma closure retornada sobrevive à função, então precisa ser `move`
+                Some(Expr {
+                    node:
+                        Expression::ArrowFunction {
+                            params,
+                            return_type,
+                            body,
+                        },
+                    ..
+                }) => format!(
+                    "    return {};\n",
+                    self.generate_arrow_function(params, return_type, body, true)
+                ),
+                Some(expr) => format!("    return {};\n", self.generate_expression(expr)),
+                None => "    return;\n".to_string(),
+            },
+            Statement::Expression(expr) => {
+                format!("    {};\n", self.generate_effect(expr))
+            }
+        }
+    }
+
+    fn generate_variable_declaration(
+        &mut self,
+        name: &Ident,
+        type_annotation: &Option<Type>,
+        value: &Option<Expr>,
+    ) -> String {
+        let ty = match type_annotation {
+            Some(ty) => Some(ty.clone()),
+            None => value.as_ref().and_then(|value| self.type_of(value)),
+        };
+        let integer = ty == Some(Type::Number)
+            && value.as_ref().is_some_and(narrowing::is_integer_literal)
+            && !self.non_integral_after.contains(&name.node);
+
+        // Closures não têm um tipo nomeável em Rust; deixamos o compilador inferir
+        let type_str = match type_annotation {
+            _ if integer => ": i64".to_string(),
+            None | Some(Type::Function { .. }) => String::new(),
+            Some(ty) => format!(": {}", rust_type(ty)),
+        };
+
+        // O valor é gerado antes da declaração, que pode esconder outra variável
+        // de mesmo nome usada no próprio valor
+        let value_str = match value {
+            Some(expr) if integer => format!(" = {}", integer_literal(expr)),
+            Some(expr) => format!(" = {}", self.generate_owned(expr)),
+            None => String::new(),
+        };
+        if integer {
+            self.declare(&name.node, Binding::integer());
+        } else {
+            let mut binding = Binding::new(ty);
+            if let Some(Expression::ArrowFunction { params, body, .. }) =
+                value.as_ref().map(|value| &value.node)
+            {
+                binding.captures = liveness::captured_variables(params, body);
+            }
+            self.declare(&name.node, binding);
+        }
+
+        // Closures que alteram variáveis capturadas são `FnMut` e também
+        // precisam de `mut` para serem chamadas
+        let mut assigned = self.assigned_after.contains(&name.node);
+        if let Some(value) = value {
+            if matches!(value.node, Expression::ArrowFunction { .. }) {
+                let mut captured = HashSet::new();
+                liveness::expression_assigned_names(value, &mut captured);
+                assigned |= !captured.is_empty();
+            }
+        }
+        let mut_str = if assigned { "mut " } else { "" };
+
+        format!("    let {}{}{}{};\n", mut_str, name.node, type_str, value_str)
+    }
+
+    fn generate_arrow_function(
+        &mut self,
+        params: &[Parameter],
+        return_type: &Option<Type>,
+        body: &ArrowBody,
+        force_move: bool,
+    ) -> String {
+        // Sem nenhuma captura usada depois desta declaração, a closure pode
+        // tomar posse das variáveis; caso contrário, ela as pega emprestadas.
+        let captured = liveness::captured_variables(params, body);
+        let is_move = force_move
+            || (!captured.is_empty() && captured.iter().all(|name| !self.live_after.contains(name)));
+
+        let mut assigned = HashSet::new();
+        match body {
+            ArrowBody::Expression(expr) => liveness::expression_assigned_names(expr, &mut assigned),
+            ArrowBody::Block(stmts) => liveness::block_assigned_names(stmts, &[], &mut assigned),
+        }
+        let mut code = format!(
+            "{}|{}|",
+            if is_move { "move " } else { "" },
+            generate_parameters(params, &assigned)
+        );
+        if let Some(ty) = return_type.as_ref().filter(|ty| **ty != Type::Void) {
+            code.push_str(&format!(" -> {}", rust_type(ty)));
+        }
+
+        self.scopes.push(HashMap::new());
+        for param in params {
+            self.declare(&param.name.node, Binding::new(param.type_annotation.clone()));
+        }
+        match body {
+            ArrowBody::Expression(expr) => {
+                let value = match &expr.node {
+                    // `a => b => a + b`: a closure interna é retornada pela externa
+                    Expression::ArrowFunction {
+                        params,
+                        return_type,
+                        body,
+                    } => self.generate_arrow_function(params, return_type, body, true),
+                    _ => self.generate_expression(expr),
+                };
+                if return_type.is_some() {
+                    code.push_str(&format!(" {{ {} }}", value));
+                } else {
+                    code.push_str(&format!(" {}", value));
+                }
+            }
+            ArrowBody::Block(stmts) => {
+                code.push_str(" {\n");
+                code.push_str(&self.generate_block(stmts, &HashSet::new()));
+                code.push_str("    }");
+            }
+        }
+        self.scopes.pop();
+
+        code
+    }
+
+    // Expressão usada como condição: valores que não são `boolean` são
+    // convertidos pela regra de verdade do JavaScript.
+    fn generate_condition(&mut self, expr: &Expr) -> String {
+        match &expr.node {
+            Expression::BinaryOp { left, op, right } if op.is_logical() => {
+                self.generate_logical_condition(left, op, right)
+            }
+            _ if self.type_of(expr) == Some(Type::Boolean) => self.generate_expression(expr),
+            Expression::Identifier(name) if self.is_integer(name) => format!("{} != 0", name),
+            _ => {
+                self.runtime.insert(runtime::TRUTHY);
+                let code = self.generate_expression(expr);
+                match &expr.node {
+                    Expression::Identifier(_) | Expression::Call { .. } => {
+                        format!("{}.is_truthy()", code)
+                    }
+                    _ => format!("({}).is_truthy()", code),
+                }
+            }
+        }
+    }
+
+    // `a && b` é verdadeiro exatamente quando os dois lados são
+    fn generate_logical_condition(
+        &mut self,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+    ) -> String {
+        let left_code = self.generate_condition_operand(left, op);
+        let right_code = self.generate_condition_operand(right, op);
+        format!("{} {} {}", left_code, op, right_code)
+    }
+
+    // Lado de um `&&`/`||` gerado como condição; `a || b` dentro de um
+    // `&&` precisa de parênteses.
+    fn generate_condition_operand(&mut self, expr: &Expr, parent: &BinaryOperator) -> String {
+        let code = self.generate_condition(expr);
+        match &expr.node {
+            Expression::BinaryOp { op, .. } if op.is_logical() && op != parent => {
+                format!("({})", code)
+            }
+            _ => code,
+        }
+    }
+
+    // Operando de uma operação binária, entre parênteses quando a ordem de
+    // avaliação do Rust seria diferente da do TypeScript. Em `==`/`!=`, uma
+    // string literal é comparada como `&str`, sem alocar.
+    fn generate_operand(&mut self, expr: &Expr, parent: &BinaryOperator, is_right: bool) -> String {
+        match &expr.node {
+            Expression::BinaryOp { op, .. } => {
+                let needs_parens = op.precedence() < parent.precedence()
+                    || (op.precedence() == parent.precedence() && is_right)
+                    || (op.is_comparison() && parent.is_comparison());
+                let code = self.generate_expression(expr);
+                if needs_parens {
+                    format!("({})", code)
+                } else {
+                    code
+                }
+            }
+            Expression::Conditional { .. } => format!("({})", self.generate_expression(expr)),
+            // Sem parênteses, o `<` depois de `x as f64` seria lido como um tipo genérico
+            Expression::Identifier(name) if self.is_integer(name) && parent.is_comparison() => {
+                format!("({} as f64)", name)
+            }
+            Expression::StringLiteral(s)
+                if matches!(
+                    parent,
+                    BinaryOperator::Equal
+                        | BinaryOperator::NotEqual
+                        | BinaryOperator::StrictEqual
+                        | BinaryOperator::StrictNotEqual
+                ) =>
+            {
+                format!("{:?}", s)
+            }
+            _ => self.generate_expression(expr),
+        }
+    }
+
+    // Forma inteira de um literal inteiro ou de uma variável gerada como `i64`
+    fn generate_integer(&self, expr: &Expr) -> Option<String> {
+        match &expr.node {
+            Expression::Identifier(name) if self.is_integer(name) => Some(name.clone()),
+            _ if narrowing::is_integer_literal(expr) => Some(integer_literal(expr)),
+            _ => None,
+        }
+    }
+
+    // Conversão `ToInt32`/`ToUint32` do JavaScript, usada pelos operadores
+    // bit a bit. Literais são convertidos aqui, e inteiros já estão dentro
+    // do intervalo seguro, em que o `as` fica com os 32 bits menos
+    // significativos; os demais valores passam pelo runtime.
+    fn generate_int32(&mut self, expr: &Expr, target: &str) -> String {
+        if let Expression::NumberLiteral(_) | Expression::Unary { .. } = &expr.node {
+            if narrowing::is_integer_literal(expr) {
+                let value = integer_value(expr);
+                return match target {
+                    "u32" => (value as u32).to_string(),
+                    _ => (value as i32).to_string(),
+                };
+            }
+        }
+        match &expr.node {
+            Expression::Identifier(name) if self.is_integer(name) => {
+                format!("({} as {})", name, target)
+            }
+            _ => {
+                self.runtime.insert(runtime::INT32);
+                let function = if target == "u32" { "to_uint32" } else { "to_int32" };
+                format!("{}({})", function, self.generate_expression(expr))
+            }
+        }
+    }
+
+    fn generate_update(&self, name: &Ident, op: &UpdateOperator) -> String {
+        let one = if self.is_integer(&name.node) {
+            "1"
+        } else {
+            "1.0"
+        };
+        match op {
+            UpdateOperator::Increment => format!("{} += {}", name.node, one),
+            UpdateOperator::Decrement => format!("{} -= {}", name.node, one),
+        }
+    }
+
+    // `a && b` e `a || b` que não são simplesmente booleanos
+    fn generate_logical(&mut self, left: &Expr, op: &BinaryOperator, right: &Expr) -> String {
+        let same_type = match self.type_of(left) {
+            Some(Type::Boolean) | None => false,
+            left_type => left_type == self.type_of(right),
+        };
+        if !same_type {
+            // Booleanos: o valor de verdade é o próprio resultado. Operandos
+            // de tipos diferentes só são aceitos pelo checker em condições.
+            return self.generate_logical_condition(left, op, right);
+        }
+
+        // Mesmo tipo: o resultado é um dos operandos, como no JavaScript
+        self.runtime.insert(runtime::TRUTHY);
+        let left_code = self.generate_owned(left);
+        let right_code = self.generate_owned(right);
+        let (when_truthy, when_falsy) = match op {
+            BinaryOperator::And => (right_code, "valor".to_string()),
+            _ => ("valor".to_string(), right_code),
+        };
+        format!(
+            "{{ let valor = {}; if valor.is_truthy() {{ {} }} else {{ {} }} }}",
+            left_code, when_truthy, when_falsy
+        )
+    }
+
+    // Valor que vai ser movido para outro lugar: variáveis que não são `Copy`
+    // são clonadas para continuarem válidas.
+    fn generate_owned(&mut self, expr: &Expr) -> String {
+        match &expr.node {
+            Expression::Identifier(name) => self.owned_name(name),
+            _ => self.generate_expression(expr),
+        }
+    }
+
+    fn owned_name(&self, name: &str) -> String {
+        if self.is_integer(name) {
+            return format!("{} as f64", name);
+        }
+        match self.lookup(name) {
+            Some(Type::String | Type::Array(_)) => format!("{}.clone()", name),
+            _ => name.to_string(),
+        }
+    }
+
+    // Expressão avaliada só pelo efeito, como uma declaração ou o incremento
+    // de um `for`: atribuições não precisam produzir o valor atribuído.
+    fn generate_effect(&mut self, expr: &Expr) -> String {
+        match &expr.node {
+            Expression::Assignment { name, op, value } => self.generate_assignment(name, op, value),
+            Expression::Update { name, op, .. } => self.generate_update(name, op),
+            _ => self.generate_expression(expr),
+        }
+    }
+
+    fn generate_assignment(
+        &mut self,
+        name: &Ident,
+        op: &Option<BinaryOperator>,
+        value: &Expr,
+    ) -> String {
+        // Variáveis geradas como `i64` só recebem literais inteiros ou `x = x ± n`
+        if self.is_integer(&name.node) {
+            if let (None, Some((op, step))) = (op, narrowing::self_increment(&name.node, value)) {
+                return format!("{} {}= {}", name.node, op, integer_literal(step));
             }
+            let op = op.as_ref().map_or(String::new(), |op| op.to_string());
+            return format!("{} {}= {}", name.node, op, integer_literal(value));
         }
+
+        let Some(op) = op else {
+            return format!("{} = {}", name.node, self.generate_owned(value));
+        };
+
+        // `texto += valor` concatena uma referência ao texto do valor
+        if *op == BinaryOperator::Add && self.lookup(&name.node) == Some(&Type::String) {
+            if self.type_of(value) == Some(Type::Number) {
+                return format!("{} += &{}", name.node, self.generate_number_text(value));
+            }
+            let value_code = self.generate_expression(value);
+            let value_code = match &value.node {
+                Expression::BinaryOp { .. }
+                | Expression::Unary { .. }
+                | Expression::Conditional { .. } => format!("({})", value_code),
+                Expression::Identifier(name) if self.is_integer(name) => {
+                    format!("({})", value_code)
+                }
+                _ => value_code,
+            };
+            return match self.type_of(value) {
+                Some(Type::String) => format!("{} += &{}", name.node, value_code),
+                _ => format!("{} += &{}.to_string()", name.node, value_code),
+            };
+        }
+
+        format!("{} {}= {}", name.node, op, self.generate_expression(value))
     }
 
-    fn generate_expression(&self, expr: &Expression) -> String {
-        match expr {
-            Expression::StringLiteral(s) => {
-                let s = s.trim_matches('"');
-                format!("String::from({:?})", s)
+    // `a + b + c` com alguma string; as somas de números à esquerda da primeira
+    // string, como em `1 + 2 + "x"`, continuam sendo somas.
+    fn generate_concatenation(&mut self, expr: &Expr) -> String {
+        let mut operands = Vec::new();
+        let mut current = expr;
+        while let Expression::BinaryOp {
+            left,
+            op: BinaryOperator::Add,
+            right,
+        } = &current.node
+        {
+            if self.type_of(current) != Some(Type::String) {
+                break;
+            }
+            operands.push(&**right);
+            current = left;
+        }
+        operands.push(current);
+        operands.reverse();
+
+        let mut format_string = String::new();
+        let mut values = String::new();
+        for operand in operands {
+            match &operand.node {
+                Expression::StringLiteral(s) => format_string.push_str(&format_text(s)),
+                _ => {
+                    let value = match self.type_of(operand) {
+                        Some(Type::Number) => self.generate_number_text(operand),
+                        _ => self.generate_expression(operand),
+                    };
+                    format_string.push_str("{}");
+                    values.push_str(", ");
+                    values.push_str(&value);
+                }
             }
+        }
+        format!("format!(\"{}\"{})", format_string, values)
+    }
+
+    // Texto de um número como no `String(x)` do JavaScript, que o `Display`
+    // do `f64` não reproduz: `Infinity`, `1e+21` e `0` para `-0`
+    fn generate_number_text(&mut self, expr: &Expr) -> String {
+        self.runtime.insert(runtime::INSPECT);
+        self.runtime.insert(runtime::FORMAT);
+        format!("Format::to_js_string({})", self.generate_reference(expr))
+    }
 
-            Expression::NumberLiteral(n) => n.to_string(),
+    fn generate_expression(&mut self, expr: &Expr) -> String {
+        match &expr.node {
+            Expression::StringLiteral(s) => format!("String::from({:?})", s),
+
+            Expression::NumberLiteral(n) if n.is_infinite() => "f64::INFINITY".to_string(),
+            Expression::NumberLiteral(n) => format!("{:?}", n),
+            Expression::BooleanLiteral(b) => b.to_string(),
+            Expression::Identifier(name) if self.is_integer(name) => format!("{} as f64", name),
             Expression::Identifier(name) => name.clone(),
             // 👇 Adicione isso:
             Expression::BinaryOp { left, op, right } => {
-                let left_is_string = matches!(**left, Expression::StringLiteral(_));
-                let right_is_identifier_or_number = matches!(
-                    **right,
-                    Expression::Identifier(_) | Expression::NumberLiteral(_)
-                );
+                // Concatenação com string: a cadeia inteira vira um `format!`
+                if *op == BinaryOperator::Add && self.type_of(expr) == Some(Type::String) {
+                    return self.generate_concatenation(expr);
+                }
 
-                if *op == BinaryOperator::Add && left_is_string && right_is_identifier_or_number {
-                    // Detectamos concatenação com string à esquerda: usar format!
-                    let left_str = if let Expression::StringLiteral(s) = &**left {
-                        s.trim_matches('"').to_string()
-                    } else {
-                        "".to_string()
-                    };
+                match op {
+                    BinaryOperator::And | BinaryOperator::Or => {
+                        return self.generate_logical(left, op, right)
+                    }
+                    // Expoentes inteiros usam `powi`, mais rápido que `powf`
+                    BinaryOperator::Exponent => {
+                        let base = self.generate_expression(left);
+                        return match &right.node {
+                            Expression::NumberLiteral(n)
+                                if n.fract() == 0.0 && n.abs() <= 1024.0 =>
+                            {
+                                format!("f64::powi({}, {})", base, n)
+                            }
+                            _ => {
+                                format!("f64::powf({}, {})", base, self.generate_expression(right))
+                            }
+                        };
+                    }
+                    // Os operadores bit a bit trabalham sobre inteiros de 32 bits, e os
+                    // deslocamentos usam só os 5 bits menos significativos da quantidade
+                    BinaryOperator::BitAnd | BinaryOperator::BitOr | BinaryOperator::BitXor => {
+                        return format!(
+                            "f64::from({} {} {})",
+                            self.generate_int32(left, "i32"),
+                            op,
+                            self.generate_int32(right, "i32")
+                        );
+                    }
+                    BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => {
+                        let function = match op {
+                            BinaryOperator::ShiftLeft => "i32::wrapping_shl",
+                            _ => "i32::wrapping_shr",
+                        };
+                        return format!(
+                            "f64::from({}({}, {}))",
+                            function,
+                            self.generate_int32(left, "i32"),
+                            self.generate_int32(right, "u32")
+                        );
+                    }
+                    BinaryOperator::UnsignedShiftRight => {
+                        return format!(
+                            "f64::from(u32::wrapping_shr({}, {}))",
+                            self.generate_int32(left, "u32"),
+                            self.generate_int32(right, "u32")
+                        );
+                    }
+                    _ => {}
+                }
 
-                    let right_str = self.generate_expression(right);
-                    return format!(
-                        "format!(\"{}{{}}\", {})",
-                        left_str.replace('"', "\\\""),
-                        right_str
-                    );
+                // Comparações entre inteiros dispensam a conversão para `f64`
+                if op.is_comparison() {
+                    if let (Some(left_code), Some(right_code)) =
+                        (self.generate_integer(left), self.generate_integer(right))
+                    {
+                        let op_code = match op {
+                            BinaryOperator::StrictEqual => "==",
+                            BinaryOperator::StrictNotEqual => "!=",
+                            _ => return format!("{} {} {}", left_code, op, right_code),
+                        };
+                        return format!("{} {} {}", left_code, op_code, right_code);
+                    }
                 }
 
                 // caso padrão
-                let left_code = self.generate_expression(left);
-                let right_code = self.generate_expression(right);
+                let left_code = self.generate_operand(left, op, false);
+                let right_code = self.generate_operand(right, op, true);
                 let op_code = match op {
                     BinaryOperator::Add => "+",
                     BinaryOperator::Subtract => "-",
                     BinaryOperator::Multiply => "*",
                     BinaryOperator::Divide => "/",
+                    BinaryOperator::Modulo => "%",
                     BinaryOperator::LessThan => "<",
                     BinaryOperator::GreaterThan => ">",
+                    BinaryOperator::LessEqual => "<=",
+                    BinaryOperator::GreaterEqual => ">=",
+                    // Os operandos são primitivos do mesmo tipo, então `==` e `===`
+                    // coincidem com o `==` do Rust
+                    BinaryOperator::Equal | BinaryOperator::StrictEqual => "==",
+                    BinaryOperator::NotEqual | BinaryOperator::StrictNotEqual => "!=",
+                    _ => unreachable!("operador {} gerado acima", op),
                 };
                 format!("{} {} {}", left_code, op_code, right_code)
             }
-            Expression::Assignment { name, value } => {
-                format!("{} = {}", name, self.generate_expression(value))
+            // Usadas como valor, atribuições resultam no novo valor da variável
+            Expression::Assignment { name, op, value } => format!(
+                "{{ {}; {} }}",
+                self.generate_assignment(name, op, value),
+                self.owned_name(&name.node)
+            ),
+            Expression::Conditional {
+                condition,
+                consequent,
+                alternate,
+            } => format!(
+                "if {} {{ {} }} else {{ {} }}",
+                self.generate_condition(condition),
+                self.generate_owned(consequent),
+                self.generate_owned(alternate)
+            ),
+            // `x++` resulta no valor anterior ao incremento; `++x`, no novo
+            Expression::Update { name, op, prefix } => {
+                let value = self.owned_name(&name.node);
+                if *prefix {
+                    format!("{{ {}; {} }}", self.generate_update(name, op), value)
+                } else {
+                    format!(
+                        "{{ let anterior = {}; {}; anterior }}",
+                        value,
+                        self.generate_update(name, op)
+                    )
+                }
+            }
+            Expression::Call { callee, args } => {
+                // Os parâmetros são recebidos por valor; variáveis passadas como
+                // argumento são clonadas para continuarem válidas depois da chamada.
+                // Funções, que podem ser parâmetros `impl Fn` sem `Clone`, são
+                // passadas por referência, que também implementa `Fn`.
+                let args_str = args
+                    .iter()
+                    .map(|arg| match &arg.node {
+                        Expression::Identifier(name)
+                            if matches!(self.lookup(name), Some(Type::Function { .. })) =>
+                        {
+                            format!("&{}", name)
+                        }
+                        Expression::Identifier(name) if !self.is_integer(name) => {
+                            format!("{}.clone()", name)
+                        }
+                        _ => self.generate_expression(arg),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}({})", self.generate_expression(callee), args_str)
+            }
+            Expression::ArrowFunction {
+                params,
+                return_type,
+                body,
+            } => self.generate_arrow_function(params, return_type, body, false),
+            Expression::Unary {
+                op: UnaryOperator::Not,
+                operand,
+            } => {
+                let code = self.generate_condition(operand);
+                match &operand.node {
+                    Expression::BinaryOp { .. } | Expression::Conditional { .. } => {
+                        format!("!({})", code)
+                    }
+                    // A condição de um inteiro é `x != 0`, e `!x != 0` seria o
+                    // `!` bit a bit do `i64`
+                    Expression::Identifier(name) if self.is_integer(name) => {
+                        format!("!({})", code)
+                    }
+                    _ => format!("!{}", code),
+                }
+            }
+            // `~x` do JavaScript é o `!x` do Rust sobre o inteiro de 32 bits
+            Expression::Unary { op, operand } => {
+                if *op == UnaryOperator::BitNot {
+                    return format!("f64::from(!{})", self.generate_int32(operand, "i32"));
+                }
+                let code = self.generate_expression(operand);
+                match &operand.node {
+                    Expression::BinaryOp { .. }
+                    | Expression::Unary { .. }
+                    | Expression::Conditional { .. } => format!("-({})", code),
+                    _ => format!("-{}", code),
+                }
             }
             Expression::ArrayLiteral(elements) => {
                 let inner = elements
@@ -204,3 +1336,163 @@ edition = "2021"
         }
     }
 }
+
+// `[]`, `[[]]` etc.: arrays cujas folhas são todas arrays vazios
+fn is_untyped_array(expr: &Expr) -> bool {
+    match &expr.node {
+        Expression::ArrayLiteral(elements) => elements.iter().all(is_untyped_array),
+        _ => false,
+    }
+}
+
+fn generate_untyped_array(expr: &Expr) -> String {
+    match &expr.node {
+        Expression::ArrayLiteral(elements) if !elements.is_empty() => format!(
+            "vec![{}]",
+            elements
+                .iter()
+                .map(generate_untyped_array)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        _ => "Vec::<f64>::new()".to_string(),
+    }
+}
+
+// Reconhece `for (let i: number = a; i < b; i = i + 1)` em que nem `i` nem
+// os nomes usados em `b` são alterados no corpo, que vira `for i in a..b`.
+fn simple_counter<'a>(
+    init: &'a Option<Box<Stmt>>,
+    condition: &'a Option<Expr>,
+    update: &'a Option<Expr>,
+    body: &[Stmt],
+) -> Option<(&'a str, &'a Expr, &'a Expr)> {
+    let (name, start) = match &init.as_deref()?.node {
+        Statement::VariableDeclaration {
+            name,
+            type_annotation: Some(Type::Number),
+            value: Some(start),
+            ..
+        } => (name.node.as_str(), start),
+        _ => return None,
+    };
+
+    let end = match &condition.as_ref()?.node {
+        Expression::BinaryOp {
+            left,
+            op: BinaryOperator::LessThan,
+            right,
+        } if matches!(&left.node, Expression::Identifier(n) if n == name) => right,
+        _ => return None,
+    };
+
+    // `i = i + 1`, `i += 1`, `i++` ou `++i`
+    match &update.as_ref()?.node {
+        Expression::Assignment {
+            name: target,
+            op: None,
+            value,
+        } if target.node == name => match &value.node {
+            Expression::BinaryOp {
+                left,
+                op: BinaryOperator::Add,
+                right,
+            } if matches!(&left.node, Expression::Identifier(n) if n == name)
+                && matches!(right.node, Expression::NumberLiteral(n) if n == 1.0) => {}
+            _ => return None,
+        },
+        Expression::Assignment {
+            name: target,
+            op: Some(BinaryOperator::Add),
+            value,
+        } if target.node == name
+            && matches!(value.node, Expression::NumberLiteral(n) if n == 1.0) => {}
+        Expression::Update {
+            name: target,
+            op: UpdateOperator::Increment,
+            ..
+        } if target.node == name => {}
+        _ => return None,
+    }
+
+    let mut assigned = HashSet::new();
+    liveness::assigned_names(body, &mut assigned);
+    let mut bound_names = HashSet::new();
+    liveness::expression_names(end, &mut bound_names);
+
+    let stable = !assigned.contains(name)
+        && assigned.is_disjoint(&bound_names)
+        && narrowing::is_integer_literal(start)
+        && is_simple_operand(end);
+    stable.then_some((name, start, end.as_ref()))
+}
+
+// Valor de um literal inteiro, possivelmente negativo
+fn integer_value(expr: &Expr) -> i64 {
+    match &expr.node {
+        Expression::NumberLiteral(n) => *n as i64,
+        Expression::Unary { operand, .. } => -integer_value(operand),
+        _ => 0,
+    }
+}
+
+fn integer_literal(expr: &Expr) -> String {
+    integer_value(expr).to_string()
+}
+
+// Expressões sem efeitos colaterais, que podem ser avaliadas uma única vez
+fn is_simple_operand(expr: &Expr) -> bool {
+    match &expr.node {
+        Expression::NumberLiteral(_) | Expression::Identifier(_) => true,
+        Expression::BinaryOp { left, right, .. } => {
+            is_simple_operand(left) && is_simple_operand(right)
+        }
+        _ => false,
+    }
+}
+
+// Texto fixo dentro da string format de `println!` e `format!`: as chaves são
+// dobradas e aspas, barras e caracteres de controle viram escapes do Rust
+fn format_text(text: &str) -> String {
+    let escaped = format!("{:?}", text.replace('{', "{{").replace('}', "}}"));
+    escaped[1..escaped.len() - 1].to_string()
+}
+
+// `assigned` são os nomes que recebem atribuição no corpo da função
+fn generate_parameters(params: &[Parameter], assigned: &HashSet<String>) -> String {
+    params
+        .iter()
+        .map(|param| {
+            let mut_str = if assigned.contains(&param.name.node) {
+                "mut "
+            } else {
+                ""
+            };
+            match &param.type_annotation {
+                Some(ty) => format!("{}{}: {}", mut_str, param.name.node, rust_type(ty)),
+                None => format!("{}{}", mut_str, param.name.node),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn rust_type(ty: &Type) -> String {
+    match ty {
+        Type::String => "String".to_string(),
+        Type::Number => "f64".to_string(),
+        Type::Boolean => "bool".to_string(),
+        Type::Array(inner) => format!("Vec<{}>", rust_type(inner)),
+        Type::Void => "()".to_string(),
+        Type::Function {
+            params,
+            return_type,
+        } => {
+            let params = params.iter().map(rust_type).collect::<Vec<_>>().join(", ");
+            match **return_type {
+                Type::Void => format!("impl Fn({})", params),
+                ref ty => format!("impl Fn({}) -> {}", params, rust_type(ty)),
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::ast::{ArrowBody, BinaryOperator, Expr, Expression, Statement, Stmt, UnaryOperator};
+use std::collections::HashSet;
+
+// Um `number` só vira inteiro no Rust quando todo valor que ele pode assumir
+// é um inteiro exato: começa com um literal inteiro e só recebe literais
+// inteiros, `++`, `--`, `+=`/`-=` de literais inteiros ou `x = x + 1`. Enquanto
+// o valor não passa de `MAX_SAFE_INTEGER`, o resultado observável é o mesmo de
+// um `f64`; acima disso o `i64` é exato e o `f64` do JavaScript arredonda.
+
+// Maior inteiro que um `f64` representa sem perder precisão
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+pub fn is_integer_literal(expr: &Expr) -> bool {
+    match &expr.node {
+        Expression::NumberLiteral(n) => n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER,
+        Expression::Unary {
+            op: UnaryOperator::Negate,
+            operand,
+        } => matches!(operand.node, Expression::NumberLiteral(_)) && is_integer_literal(operand),
+        _ => false,
+    }
+}
+
+// `x = x + 1` ou `x = x - 1`, com qualquer literal inteiro
+pub fn self_increment<'a>(name: &str, value: &'a Expr) -> Option<(&'a BinaryOperator, &'a Expr)> {
+    match &value.node {
+        Expression::BinaryOp { left, op, right }
+            if matches!(op, BinaryOperator::Add | BinaryOperator::Subtract)
+                && matches!(&left.node, Expression::Identifier(n) if n == name)
+                && is_integer_literal(right) =>
+        {
+            Some((op, right))
+        }
+        _ => None,
+    }
+}
+
+fn is_integral_write(name: &str, op: &Option<BinaryOperator>, value: &Expr) -> bool {
+    match op {
+        None => is_integer_literal(value) || self_increment(name, value).is_some(),
+        Some(BinaryOperator::Add | BinaryOperator::Subtract) => is_integer_literal(value),
+        Some(_) => false,
+    }
+}
+
+// Nomes que recebem, em algum ponto do trecho, um valor que pode não ser inteiro
+pub fn non_integral_writes(stmts: &[Stmt], names: &mut HashSet<String>) {
+    for stmt in stmts {
+        statement_non_integral_writes(stmt, names);
+    }
+}
+
+pub fn statement_non_integral_writes(stmt: &Stmt, names: &mut HashSet<String>) {
+    match &stmt.node {
+        Statement::Console { args, .. } => {
+            for arg in args {
+                expression_non_integral_writes(arg, names);
+            }
+        }
+        Statement::VariableDeclaration { value, .. } | Statement::Return(value) => {
+            if let Some(value) = value {
+                expression_non_integral_writes(value, names);
+            }
+        }
+        Statement::IfStatement {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            expression_non_integral_writes(condition, names);
+            non_integral_writes(then_branch, names);
+            if let Some(else_branch) = else_branch {
+                non_integral_writes(else_branch, names);
+            }
+        }
+        Statement::WhileStatement { condition, body } => {
+            expression_non_integral_writes(condition, names);
+            non_integral_writes(body, names);
+        }
+        Statement::ForStatement {
+            init,
+            condition,
+            update,
+            body,
+        } => {
+            if let Some(init) = init {
+                statement_non_integral_writes(init, names);
+            }
+            for expr in condition.iter().chain(update) {
+                expression_non_integral_writes(expr, names);
+            }
+            non_integral_writes(body, names);
+        }
+        Statement::ForOfStatement { iterable, body, .. } => {
+            expression_non_integral_writes(iterable, names);
+            non_integral_writes(body, names);
+        }
+        Statement::Break | Statement::Continue => {}
+        Statement::Assignment { name, op, value } => {
+            if !is_integral_write(&name.node, op, value) {
+                names.insert(name.node.clone());
+            }
+            expression_non_integral_writes(value, names);
+        }
+        Statement::FunctionDeclaration { body, .. } => non_integral_writes(body, names),
+        Statement::Expression(expr) => expression_non_integral_writes(expr, names),
+    }
+}
+
+pub fn expression_non_integral_writes(expr: &Expr, names: &mut HashSet<String>) {
+    match &expr.node {
+        Expression::Assignment { name, op, value } => {
+            if !is_integral_write(&name.node, op, value) {
+                names.insert(name.node.clone());
+            }
+            expression_non_integral_writes(value, names);
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            expression_non_integral_writes(left, names);
+            expression_non_integral_writes(right, names);
+        }
+        Expression::Unary { operand, .. } => expression_non_integral_writes(operand, names),
+        Expression::Conditional {
+            condition,
+            consequent,
+            alternate,
+        } => {
+            expression_non_integral_writes(condition, names);
+            expression_non_integral_writes(consequent, names);
+            expression_non_integral_writes(alternate, names);
+        }
+        Expression::ArrayLiteral(elements) => {
+            for element in elements {
+                expression_non_integral_writes(element, names);
+            }
+        }
+        Expression::Call { callee, args } => {
+            expression_non_integral_writes(callee, names);
+            for arg in args {
+                expression_non_integral_writes(arg, names);
+            }
+        }
+        Expression::ArrowFunction { body, .. } => match body {
+            ArrowBody::Expression(expr) => expression_non_integral_writes(expr, names),
+            ArrowBody::Block(stmts) => non_integral_writes(stmts, names),
+        },
+        Expression::StringLiteral(_)
+        | Expression::NumberLiteral(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::Identifier(_)
+        | Expression::Update { .. } => {}
+    }
+}
@@ -0,0 +1,502 @@
+// Funções de apoio copiadas para o código gerado quando algum trecho precisa
+// reproduzir um comportamento do JavaScript que o Rust não tem.
+
+// Valor de verdade de um valor em `if`, `while`, `&&`, `||` e `!`
+pub const TRUTHY: &str = r#"trait Truthy {
+    fn is_truthy(&self) -> bool;
+}
+
+impl Truthy for bool {
+    fn is_truthy(&self) -> bool {
+        *self
+    }
+}
+
+impl Truthy for f64 {
+    fn is_truthy(&self) -> bool {
+        *self != 0.0 && !self.is_nan()
+    }
+}
+
+impl Truthy for String {
+    fn is_truthy(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<T> Truthy for Vec<T> {
+    fn is_truthy(&self) -> bool {
+        true
+    }
+}
+"#;
+
+// Conversões `ToInt32` e `ToUint32` dos operadores bit a bit: os 32 bits
+// menos significativos da parte inteira, e 0 para NaN e infinitos
+pub const INT32: &str = r#"fn to_uint32(value: f64) -> u32 {
+    if value.is_finite() {
+        value.trunc().rem_euclid(4294967296.0) as u32
+    } else {
+        0
+    }
+}
+
+// Só o `>>>` não usa esta conversão
+#[allow(dead_code)]
+fn to_int32(value: f64) -> i32 {
+    to_uint32(value) as i32
+}
+"#;
+
+// Texto de um valor no `console.log`, no formato do `util.inspect` do Node
+pub const INSPECT: &str = r#"trait Inspect {
+    // Se o valor é um `number`; arrays de números são alinhados à direita
+    const NUMBER: bool = false;
+
+    // Argumento do `console.log`: só as strings mudam, aparecendo sem aspas
+    fn display(&self) -> String {
+        self.inspect(0, 0)
+    }
+
+    // `depth` é o nível de aninhamento e `indentation` a coluna do valor
+    fn inspect(&self, depth: usize, indentation: usize) -> String;
+}
+
+// Elementos percorridos por referência, como no `for...of` de um array
+impl<T: Inspect + ?Sized> Inspect for &T {
+    const NUMBER: bool = T::NUMBER;
+
+    fn display(&self) -> String {
+        (**self).display()
+    }
+
+    fn inspect(&self, depth: usize, indentation: usize) -> String {
+        (**self).inspect(depth, indentation)
+    }
+}
+
+impl Inspect for f64 {
+    const NUMBER: bool = true;
+
+    fn inspect(&self, _depth: usize, _indentation: usize) -> String {
+        if self.is_nan() {
+            "NaN".to_string()
+        } else if self.is_infinite() {
+            if *self > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+        } else if *self == 0.0 {
+            if self.is_sign_negative() { "-0" } else { "0" }.to_string()
+        } else if self.abs() >= 1e21 || self.abs() < 1e-6 {
+            // Notação exponencial, com o sinal do expoente sempre explícito
+            let text = format!("{:e}", self);
+            match text.split_once('e') {
+                Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+                    format!("{}e+{}", mantissa, exponent)
+                }
+                _ => text,
+            }
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl Inspect for bool {
+    fn inspect(&self, _depth: usize, _indentation: usize) -> String {
+        self.to_string()
+    }
+}
+
+impl Inspect for () {
+    fn inspect(&self, _depth: usize, _indentation: usize) -> String {
+        "undefined".to_string()
+    }
+}
+
+impl Inspect for String {
+    fn display(&self) -> String {
+        self.clone()
+    }
+
+    fn inspect(&self, _depth: usize, _indentation: usize) -> String {
+        // Aspas simples, a não ser que a string já contenha alguma
+        let quote = if !self.contains('\'') {
+            '\''
+        } else if !self.contains('"') {
+            '"'
+        } else if !self.contains('`') {
+            '`'
+        } else {
+            '\''
+        };
+        let mut text = String::from(quote);
+        for c in self.chars() {
+            match c {
+                '\n' => text.push_str("\\n"),
+                '\t' => text.push_str("\\t"),
+                '\r' => text.push_str("\\r"),
+                '\u{8}' => text.push_str("\\b"),
+                '\u{c}' => text.push_str("\\f"),
+                '\\' => text.push_str("\\\\"),
+                c if c == quote => {
+                    text.push('\\');
+                    text.push(c);
+                }
+                '\0'..='\u{1f}' | '\u{7f}'..='\u{9f}' => {
+                    text.push_str(&format!("\\x{:02X}", c as u32));
+                }
+                c => text.push(c),
+            }
+        }
+        text.push(quote);
+        text
+    }
+}
+
+impl<T: Inspect> Inspect for Vec<T> {
+    fn inspect(&self, depth: usize, indentation: usize) -> String {
+        if self.is_empty() {
+            return "[]".to_string();
+        }
+        if depth > 2 {
+            return "[Array]".to_string();
+        }
+
+        let mut entries: Vec<String> = self
+            .iter()
+            .take(100)
+            .map(|item| item.inspect(depth + 1, indentation + 2))
+            .collect();
+        let more = self.len().saturating_sub(100);
+        if more > 0 {
+            let plural = if more > 1 { "s" } else { "" };
+            entries.push(format!("... {} more item{}", more, plural));
+        }
+
+        let count = entries.len();
+        if count > 6 {
+            entries = group_entries(entries, more > 0, T::NUMBER, indentation);
+        }
+        if entries.len() == count {
+            // Cabe em uma linha de até 80 colunas
+            let start = count + indentation + 1 + 10;
+            let width = entries
+                .iter()
+                .fold(count + start, |width, entry| width + entry.chars().count());
+            if count * 2 + start <= 80 && width <= 80 {
+                let joined = entries.join(", ");
+                if !joined.contains('\n') {
+                    return format!("[ {} ]", joined);
+                }
+            }
+        }
+
+        let padding = " ".repeat(indentation);
+        let separator = format!(",\n{}  ", padding);
+        format!("[\n{}  {}\n{}]", padding, entries.join(&separator), padding)
+    }
+}
+
+// Arrays com muitos itens curtos são exibidos em colunas
+fn group_entries(
+    entries: Vec<String>,
+    has_more: bool,
+    numbers: bool,
+    indentation: usize,
+) -> Vec<String> {
+    let length = if has_more { entries.len() - 1 } else { entries.len() };
+    let widths: Vec<usize> = entries[..length]
+        .iter()
+        .map(|entry| entry.chars().count())
+        .collect();
+    let total_width: usize = widths.iter().map(|width| width + 2).sum();
+    let max_width = widths.iter().copied().max().unwrap_or(0);
+    let column_width = max_width + 2;
+    if column_width * 3 + indentation >= 80
+        || (total_width as f64 / column_width as f64 <= 5.0 && max_width > 6)
+    {
+        return entries;
+    }
+
+    let average_bias = (column_width as f64 - total_width as f64 / entries.len() as f64).sqrt();
+    let biased_width = (column_width as f64 - 3.0 - average_bias).max(1.0);
+    let columns = ((2.5 * biased_width * length as f64).sqrt() / biased_width)
+        .round()
+        .min(((80 - indentation) / column_width) as f64)
+        .min(12.0) as usize;
+    if columns <= 1 {
+        return entries;
+    }
+
+    let line_widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            let widest = (column..length)
+                .step_by(columns)
+                .map(|index| widths[index])
+                .max()
+                .unwrap_or(0);
+            widest + 2
+        })
+        .collect();
+    let mut lines = Vec::new();
+    for start in (0..length).step_by(columns) {
+        let end = (start + columns).min(length);
+        let mut line = String::new();
+        for (column, entry) in entries[start..end].iter().enumerate() {
+            let last = start + column == end - 1;
+            let cell = if last {
+                entry.clone()
+            } else {
+                format!("{}, ", entry)
+            };
+            // Números alinhados à direita, o resto à esquerda
+            let width = if last { line_widths[column] - 2 } else { line_widths[column] };
+            if numbers {
+                line.push_str(&format!("{:>1$}", cell, width));
+            } else if last {
+                line.push_str(&cell);
+            } else {
+                line.push_str(&format!("{:<1$}", cell, width));
+            }
+        }
+        lines.push(line);
+    }
+    if has_more {
+        lines.push(entries[length].clone());
+    }
+    lines
+}
+"#;
+
+// Conversões do JavaScript usadas pelos especificadores `%s`, `%d`, `%i`, `%f`
+// e `%j` de uma string format do `console.log` e pela concatenação de números
+// com strings. Cada uso precisa só de parte delas, por isso o `allow(dead_code)`.
+pub const FORMAT: &str = r#"#[allow(dead_code)]
+trait Format: Inspect {
+    // `String(valor)`
+    fn to_js_string(&self) -> String;
+
+    // `Number(valor)`
+    fn to_number(&self) -> f64 {
+        string_to_number(&self.to_js_string())
+    }
+
+    // `JSON.stringify(valor)`
+    fn to_json(&self) -> String;
+
+    // Texto do `%s`: arrays são inspecionados só no primeiro nível
+    fn format_s(&self) -> String {
+        self.display()
+    }
+}
+
+impl<T: Format + ?Sized> Format for &T {
+    fn to_js_string(&self) -> String {
+        (**self).to_js_string()
+    }
+
+    fn to_number(&self) -> f64 {
+        (**self).to_number()
+    }
+
+    fn to_json(&self) -> String {
+        (**self).to_json()
+    }
+
+    fn format_s(&self) -> String {
+        (**self).format_s()
+    }
+}
+
+impl Format for f64 {
+    fn to_js_string(&self) -> String {
+        if *self == 0.0 {
+            "0".to_string()
+        } else {
+            self.inspect(0, 0)
+        }
+    }
+
+    fn to_number(&self) -> f64 {
+        *self
+    }
+
+    fn to_json(&self) -> String {
+        if self.is_finite() {
+            self.to_js_string()
+        } else {
+            "null".to_string()
+        }
+    }
+}
+
+impl Format for bool {
+    fn to_js_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn to_number(&self) -> f64 {
+        if *self { 1.0 } else { 0.0 }
+    }
+
+    fn to_json(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Format for () {
+    fn to_js_string(&self) -> String {
+        "undefined".to_string()
+    }
+
+    fn to_json(&self) -> String {
+        "undefined".to_string()
+    }
+}
+
+impl Format for String {
+    fn to_js_string(&self) -> String {
+        self.clone()
+    }
+
+    fn to_json(&self) -> String {
+        let mut text = String::from('"');
+        for c in self.chars() {
+            match c {
+                '"' => text.push_str("\\\""),
+                '\\' => text.push_str("\\\\"),
+                '\n' => text.push_str("\\n"),
+                '\t' => text.push_str("\\t"),
+                '\r' => text.push_str("\\r"),
+                '\u{8}' => text.push_str("\\b"),
+                '\u{c}' => text.push_str("\\f"),
+                '\0'..='\u{1f}' => text.push_str(&format!("\\u{:04x}", c as u32)),
+                c => text.push(c),
+            }
+        }
+        text.push('"');
+        text
+    }
+}
+
+impl<T: Format> Format for Vec<T> {
+    fn to_js_string(&self) -> String {
+        let items: Vec<String> = self.iter().map(|item| item.to_js_string()).collect();
+        items.join(",")
+    }
+
+    fn to_json(&self) -> String {
+        let items: Vec<String> = self.iter().map(|item| item.to_json()).collect();
+        format!("[{}]", items.join(","))
+    }
+
+    // A partir do nível 3, `inspect` mostra os arrays internos como `[Array]`
+    fn format_s(&self) -> String {
+        self.inspect(2, 0)
+    }
+}
+
+#[allow(dead_code)]
+fn is_js_whitespace(c: char) -> bool {
+    c.is_whitespace() || c == '\u{feff}'
+}
+
+// `Number(texto)`: o texto inteiro precisa ser um número
+#[allow(dead_code)]
+fn string_to_number(text: &str) -> f64 {
+    let text = text.trim_matches(is_js_whitespace);
+    let radix = match text.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => 10,
+    };
+    match text {
+        "" => 0.0,
+        "Infinity" | "+Infinity" => f64::INFINITY,
+        "-Infinity" => f64::NEG_INFINITY,
+        _ if radix != 10 => match digits_value(&text[2..], radix) {
+            Some(value) if text[2..].len() == digits_length(&text[2..], radix) => value,
+            _ => f64::NAN,
+        },
+        // `parse` também aceitaria `inf` e `NaN`
+        _ if text.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c)) => {
+            text.parse().unwrap_or(f64::NAN)
+        }
+        _ => f64::NAN,
+    }
+}
+
+// `parseInt(texto)`: lê os dígitos do início e ignora o resto
+#[allow(dead_code)]
+fn parse_int(text: &str) -> f64 {
+    let text = text.trim_start_matches(is_js_whitespace);
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (radix, text) = match text.get(..2) {
+        Some("0x" | "0X") => (16, &text[2..]),
+        _ => (10, text),
+    };
+    let digits = &text[..digits_length(text, radix)];
+    let value = if radix == 10 {
+        digits.parse().ok()
+    } else {
+        digits_value(digits, radix)
+    };
+    value.map_or(f64::NAN, |value: f64| sign * value)
+}
+
+// `parseFloat(texto)`: lê o maior número decimal no início do texto
+#[allow(dead_code)]
+fn parse_float(text: &str) -> f64 {
+    let text = text.trim_start_matches(is_js_whitespace);
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+    if unsigned.starts_with("Infinity") {
+        return if text.starts_with('-') {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+    }
+
+    let sign = text.len() - unsigned.len();
+    let bytes = unsigned.as_bytes();
+    let integer = digits_length(unsigned, 10);
+    let mut end = integer;
+    let mut digits = integer;
+    if bytes.get(end) == Some(&b'.') {
+        let fraction = digits_length(&unsigned[end + 1..], 10);
+        end += 1 + fraction;
+        digits += fraction;
+    }
+    if digits == 0 {
+        return f64::NAN;
+    }
+    if let Some(b'e' | b'E') = bytes.get(end) {
+        let exponent = &unsigned[end + 1..];
+        let exponent_sign = usize::from(exponent.starts_with(['+', '-']));
+        let exponent_digits = digits_length(&exponent[exponent_sign..], 10);
+        if exponent_digits > 0 {
+            end += 1 + exponent_sign + exponent_digits;
+        }
+    }
+    text[..sign + end].parse().unwrap_or(f64::NAN)
+}
+
+// Quantidade de dígitos na base `radix` no início do texto
+#[allow(dead_code)]
+fn digits_length(text: &str, radix: u32) -> usize {
+    text.chars().take_while(|c| c.is_digit(radix)).count()
+}
+
+// Valor dos dígitos no início do texto; `None` se não houver nenhum
+#[allow(dead_code)]
+fn digits_value(text: &str, radix: u32) -> Option<f64> {
+    let digits: Vec<u32> = text.chars().map_while(|c| c.to_digit(radix)).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    Some(digits.iter().fold(0.0, |value, digit| value * radix as f64 + *digit as f64))
+}
+"#;
@@ -0,0 +1,62 @@
+use crate::diagnostics::{Diagnostic, ToDiagnostic};
+use logos::Span;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Default, Error)]
+pub enum LexErrorKind {
+    // O caractere é preenchido pelo `Lexer`, já que o logos só conhece o span
+    #[default]
+    #[error("caractere inesperado")]
+    UnknownCharacter,
+    #[error("caractere inesperado `{}`", .0.escape_debug())]
+    UnexpectedCharacter(char),
+    #[error("string não terminada")]
+    UnterminatedString,
+    #[error("separador numérico em posição inválida")]
+    InvalidNumericSeparator,
+    #[error("sequência de escape inválida `{sequence}`")]
+    InvalidEscape { sequence: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{kind}")]
+pub struct LexError {
+    pub span: Span,
+    pub kind: LexErrorKind,
+}
+
+impl ToDiagnostic for LexError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.to_string());
+        match &self.kind {
+            LexErrorKind::UnknownCharacter | LexErrorKind::UnexpectedCharacter(_) => {
+                diagnostic.with_label(self.span.clone(), "não reconhecido pelo lexer")
+            }
+            LexErrorKind::UnterminatedString => diagnostic
+                .with_label(self.span.clone(), "falta a aspa de fechamento")
+                .with_note("strings devem terminar com `\"` na mesma linha"),
+            LexErrorKind::InvalidNumericSeparator => diagnostic
+                .with_label(self.span.clone(), "`_` fora do lugar neste número")
+                .with_note("o `_` só pode aparecer entre dois dígitos, como em `1_000_000`"),
+            LexErrorKind::InvalidEscape { sequence, .. } => {
+                let (label, note) = match sequence.chars().nth(1) {
+                    Some('x') => (
+                        "`\\x` precisa de dois dígitos hexadecimais",
+                        "use, por exemplo, `\\x41` para o caractere `A`",
+                    ),
+                    Some('u') => (
+                        "não é um código Unicode válido",
+                        "use `\\u` com quatro dígitos hexadecimais, como `\\u00E9`, ou `\\u{...}` com um código de até `10FFFF`",
+                    ),
+                    _ => (
+                        "escapes octais não são permitidos",
+                        "use `\\x` ou `\\u` para escrever o caractere pelo código",
+                    ),
+                };
+                diagnostic
+                    .with_label(self.span.clone(), label)
+                    .with_note(note)
+            }
+        }
+    }
+}
@@ -1,24 +1,45 @@
+mod error;
 mod tokens;
+pub use error::{LexError, LexErrorKind};
 pub use tokens::Token;
 
 use logos::{Logos, Span};
 
 pub struct Lexer {
     tokens: Vec<(Token, Span)>,
+    errors: Vec<LexError>,
 }
 
 impl Lexer {
     pub fn new(source: &str) -> Self {
-        let lexer = Token::lexer(source);
-        let tokens: Vec<(Token, Span)> = lexer
-            .spanned()
-            .filter_map(|(token, span)| match token {
-                Ok(token) => Some((token, span)),
-                _ => None,
-            })
-            .collect();
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
 
-        Lexer { tokens }
+        let mut lexer = Token::lexer(source).spanned();
+        for (token, span) in lexer.by_ref() {
+            match token {
+                Ok(token) => tokens.push((token, span)),
+                Err(kind) => {
+                    let kind = match kind {
+                        LexErrorKind::UnknownCharacter => source[span.clone()]
+                            .chars()
+                            .next()
+                            .map_or(kind, LexErrorKind::UnexpectedCharacter),
+                        kind => kind,
+                    };
+                    errors.push(LexError { span, kind });
+                }
+            }
+        }
+        // Os escapes inválidos são guardados à parte pelo logos
+        errors.append(&mut lexer.extras);
+        errors.sort_by_key(|error| error.span.start);
+
+        Lexer { tokens, errors }
+    }
+
+    pub fn get_errors(&self) -> &[LexError] {
+        &self.errors
     }
 
     pub fn get_tokens(self) -> Vec<(Token, Span)> {
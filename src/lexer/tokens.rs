@@ -1,9 +1,18 @@
-use logos::{Logos};
+use super::{LexError, LexErrorKind};
+use crate::ast::ConsoleMethod;
+use logos::{Lexer, Logos};
+use std::fmt;
 
 #[derive(Logos, Debug, PartialEq, Clone)]
+#[logos(error = LexErrorKind)]
+// Escapes inválidos das strings, que mesmo assim viram um token
+#[logos(extras = Vec<LexError>)]
 pub enum Token {
-    #[token("console.log")]
-    ConsoleLog,
+    #[token("console.log", |_| ConsoleMethod::Log)]
+    #[token("console.info", |_| ConsoleMethod::Info)]
+    #[token("console.warn", |_| ConsoleMethod::Warn)]
+    #[token("console.error", |_| ConsoleMethod::Error)]
+    Console(ConsoleMethod),
 
     #[token("if")]
     If,
@@ -20,12 +29,39 @@ pub enum Token {
     #[token("while")]
     While,
 
+    #[token("for")]
+    For,
+
+    #[token("break")]
+    Break,
+
+    #[token("continue")]
+    Continue,
+
+    #[token("function")]
+    Function,
+
+    #[token("return")]
+    Return,
+
+    #[token("true")]
+    True,
+
+    #[token("false")]
+    False,
+
     #[token("=")]
     Equal,
 
+    #[token("=>")]
+    Arrow,
+
     #[token(":")]
     Colon,
 
+    #[token("?")]
+    Question,
+
     #[token(";")]
     Semicolon,
 
@@ -53,6 +89,33 @@ pub enum Token {
     #[token(">")]
     GreaterThan,
 
+    #[token("<=")]
+    LessEqual,
+
+    #[token(">=")]
+    GreaterEqual,
+
+    #[token("==")]
+    EqualEqual,
+
+    #[token("===")]
+    StrictEqual,
+
+    #[token("!=")]
+    NotEqual,
+
+    #[token("!==")]
+    StrictNotEqual,
+
+    #[token("&&")]
+    AndAnd,
+
+    #[token("||")]
+    OrOr,
+
+    #[token("!")]
+    Bang,
+
     #[token("+")]
     Plus,
 
@@ -65,18 +128,295 @@ pub enum Token {
     #[token("/")]
     Slash,
 
+    #[token("%")]
+    Percent,
+
+    #[token("+=")]
+    PlusEqual,
+
+    #[token("-=")]
+    MinusEqual,
+
+    #[token("*=")]
+    StarEqual,
+
+    #[token("/=")]
+    SlashEqual,
+
+    #[token("%=")]
+    PercentEqual,
+
+    #[token("++")]
+    PlusPlus,
+
+    #[token("--")]
+    MinusMinus,
+
+    #[token("**")]
+    StarStar,
+
+    #[token("&")]
+    Ampersand,
+
+    #[token("|")]
+    Pipe,
+
+    #[token("^")]
+    Caret,
+
+    #[token("~")]
+    Tilde,
+
+    #[token("<<")]
+    ShiftLeft,
+
+    #[token(">>")]
+    ShiftRight,
+
+    #[token(">>>")]
+    UnsignedShiftRight,
+
     #[token(",")]
     Comma,
 
-    #[regex(r#""([^"\\]|\\.)*""#, |lex| lex.slice().to_string())]
+    // Strings não podem atravessar linhas; sem a aspa final, o lexer reporta o erro
+    #[regex(r#""([^"\\\n]|\\.)*"?"#, string_literal)]
     StringLiteral(String),
 
     #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*", |lex| lex.slice().to_string())]
     Identifier(String),
 
-    #[regex(r"[0-9]+", |lex| lex.slice().parse::<i32>().ok())]
-    Number(i32),
+    // Decimais com expoente opcional e inteiros em hexadecimal, octal ou binário,
+    // todos aceitando `_` como separador
+    #[regex(r"[0-9][0-9_]*(\.[0-9_]*)?([eE][+-]?[0-9_]+)?", number_literal)]
+    #[regex(r"\.[0-9][0-9_]*([eE][+-]?[0-9_]+)?", number_literal)]
+    #[regex(r"0[xX][0-9a-fA-F_]+|0[oO][0-7_]+|0[bB][01_]+", number_literal)]
+    Number(f64),
 
-    #[regex(r"[ \t\n\f]+", logos::skip)]
+    #[regex(r"[ \t\r\n\f]+", logos::skip)]
     Whitespace,
+
+    // Nunca produzido pelo lexer; usado pelo parser para representar o fim da entrada
+    Eof,
+}
+
+fn string_literal(lex: &mut Lexer<Token>) -> Result<String, LexErrorKind> {
+    let slice = lex.slice();
+    let start = lex.span().start;
+
+    // A aspa final só fecha a string se não fizer parte de um escape `\"`.
+    // Todos os escapes inválidos são apontados, e não só o primeiro.
+    let mut value = String::new();
+    let mut errors = Vec::new();
+    let mut offset = 1;
+    while let Some(c) = slice[offset..].chars().next() {
+        match c {
+            '"' => {
+                lex.extras.append(&mut errors);
+                return Ok(value);
+            }
+            '\\' => match escape(&slice[offset + 1..]) {
+                Ok((decoded, length)) => {
+                    value.push(decoded);
+                    offset += 1 + length;
+                }
+                Err(length) => {
+                    let span = start + offset..start + offset + 1 + length;
+                    errors.push(LexError {
+                        kind: LexErrorKind::InvalidEscape {
+                            sequence: slice[offset..offset + 1 + length].to_string(),
+                        },
+                        span,
+                    });
+                    offset += 1 + length;
+                }
+            },
+            c => {
+                value.push(c);
+                offset += c.len_utf8();
+            }
+        }
+    }
+    Err(LexErrorKind::UnterminatedString)
+}
+
+// Decodifica o escape que segue uma `\`: devolve o caractere e quantos bytes
+// o escape ocupa, ou o tamanho do trecho inválido.
+fn escape(text: &str) -> Result<(char, usize), usize> {
+    let c = text.chars().next().ok_or(0usize)?;
+    let decoded = match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        'v' => '\u{b}',
+        '0' if !text[1..].starts_with(|c: char| c.is_ascii_digit()) => '\0',
+        // Escapes octais, como `\07`, não são permitidos no modo estrito
+        '0'..='9' => return Err(text.chars().take_while(char::is_ascii_digit).count()),
+        'x' => {
+            let digits = hex_prefix(&text[1..], 2);
+            return match u8::from_str_radix(digits, 16) {
+                Ok(code) if digits.len() == 2 => Ok((char::from(code), 3)),
+                _ => Err(1 + digits.len()),
+            };
+        }
+        'u' => {
+            let (decoded, length) = unicode_escape(&text[1..]).map_err(|length| 1 + length)?;
+            return Ok((decoded, 1 + length));
+        }
+        // Os demais caracteres escapados valem eles mesmos, como em `\"`
+        c => c,
+    };
+    Ok((decoded, c.len_utf8()))
+}
+
+// Até `max` dígitos hexadecimais no início do texto
+fn hex_prefix(text: &str, max: usize) -> &str {
+    let length = text
+        .chars()
+        .take(max)
+        .take_while(char::is_ascii_hexdigit)
+        .count();
+    &text[..length]
+}
+
+// `XXXX` ou `{X...}` depois de um `\u`; um surrogate alto só é válido seguido
+// do baixo, como em `\uD83D\uDE00`.
+fn unicode_escape(text: &str) -> Result<(char, usize), usize> {
+    if let Some(braced) = text.strip_prefix('{') {
+        let digits = hex_prefix(braced, usize::MAX);
+        if digits.is_empty() || !braced[digits.len()..].starts_with('}') {
+            return Err(1 + digits.len());
+        }
+        return u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .map(|c| (c, digits.len() + 2))
+            .ok_or(digits.len() + 2);
+    }
+
+    let code = code_unit(text).ok_or(hex_prefix(text, 4).len())?;
+    if let Some(c) = char::from_u32(code) {
+        return Ok((c, 4));
+    }
+    let low = text[4..].strip_prefix("\\u").and_then(code_unit);
+    match (code, low) {
+        (0xD800..=0xDBFF, Some(low @ 0xDC00..=0xDFFF)) => {
+            char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00))
+                .map(|c| (c, 10))
+                .ok_or(4)
+        }
+        _ => Err(4),
+    }
+}
+
+// Quatro dígitos hexadecimais, como no `\uXXXX`
+fn code_unit(text: &str) -> Option<u32> {
+    let digits = hex_prefix(text, 4);
+    match u32::from_str_radix(digits, 16) {
+        Ok(code) if digits.len() == 4 => Some(code),
+        _ => None,
+    }
+}
+
+fn number_literal(lex: &mut Lexer<Token>) -> Result<f64, LexErrorKind> {
+    let slice = lex.slice();
+    let (radix, digits) = match slice.get(..2) {
+        Some("0x" | "0X") => (16, &slice[2..]),
+        Some("0o" | "0O") => (8, &slice[2..]),
+        Some("0b" | "0B") => (2, &slice[2..]),
+        _ => (10, slice),
+    };
+
+    // Como no JavaScript, o `_` só pode aparecer entre dois dígitos
+    let chars: Vec<char> = digits.chars().collect();
+    let is_digit = |index: Option<usize>| {
+        index
+            .and_then(|index| chars.get(index))
+            .is_some_and(|c| c.is_digit(radix))
+    };
+    for (index, c) in chars.iter().enumerate() {
+        if *c == '_' && !(is_digit(index.checked_sub(1)) && is_digit(Some(index + 1))) {
+            return Err(LexErrorKind::InvalidNumericSeparator);
+        }
+    }
+
+    let digits: String = chars.iter().filter(|c| **c != '_').collect();
+    if radix == 10 {
+        // A expressão regular só aceita decimais que o Rust sabe converter
+        Ok(digits.parse().unwrap_or(f64::NAN))
+    } else {
+        Ok(digits.chars().fold(0.0, |value, c| {
+            value * f64::from(radix) + f64::from(c.to_digit(radix).unwrap_or(0))
+        }))
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Console(method) => write!(f, "`{}`", method),
+            Token::If => write!(f, "`if`"),
+            Token::Else => write!(f, "`else`"),
+            Token::Let => write!(f, "`let`"),
+            Token::Const => write!(f, "`const`"),
+            Token::While => write!(f, "`while`"),
+            Token::For => write!(f, "`for`"),
+            Token::Break => write!(f, "`break`"),
+            Token::Continue => write!(f, "`continue`"),
+            Token::Function => write!(f, "`function`"),
+            Token::Return => write!(f, "`return`"),
+            Token::True => write!(f, "`true`"),
+            Token::False => write!(f, "`false`"),
+            Token::Equal => write!(f, "`=`"),
+            Token::Arrow => write!(f, "`=>`"),
+            Token::Colon => write!(f, "`:`"),
+            Token::Question => write!(f, "`?`"),
+            Token::Semicolon => write!(f, "`;`"),
+            Token::OpenParen => write!(f, "`(`"),
+            Token::CloseParen => write!(f, "`)`"),
+            Token::OpenBrace => write!(f, "`{{`"),
+            Token::CloseBrace => write!(f, "`}}`"),
+            Token::OpenBracket => write!(f, "`[`"),
+            Token::CloseBracket => write!(f, "`]`"),
+            Token::LessThan => write!(f, "`<`"),
+            Token::GreaterThan => write!(f, "`>`"),
+            Token::LessEqual => write!(f, "`<=`"),
+            Token::GreaterEqual => write!(f, "`>=`"),
+            Token::EqualEqual => write!(f, "`==`"),
+            Token::StrictEqual => write!(f, "`===`"),
+            Token::NotEqual => write!(f, "`!=`"),
+            Token::StrictNotEqual => write!(f, "`!==`"),
+            Token::AndAnd => write!(f, "`&&`"),
+            Token::OrOr => write!(f, "`||`"),
+            Token::Bang => write!(f, "`!`"),
+            Token::Plus => write!(f, "`+`"),
+            Token::Minus => write!(f, "`-`"),
+            Token::Star => write!(f, "`*`"),
+            Token::Slash => write!(f, "`/`"),
+            Token::Percent => write!(f, "`%`"),
+            Token::PlusEqual => write!(f, "`+=`"),
+            Token::MinusEqual => write!(f, "`-=`"),
+            Token::StarEqual => write!(f, "`*=`"),
+            Token::SlashEqual => write!(f, "`/=`"),
+            Token::PercentEqual => write!(f, "`%=`"),
+            Token::PlusPlus => write!(f, "`++`"),
+            Token::MinusMinus => write!(f, "`--`"),
+            Token::StarStar => write!(f, "`**`"),
+            Token::Ampersand => write!(f, "`&`"),
+            Token::Pipe => write!(f, "`|`"),
+            Token::Caret => write!(f, "`^`"),
+            Token::Tilde => write!(f, "`~`"),
+            Token::ShiftLeft => write!(f, "`<<`"),
+            Token::ShiftRight => write!(f, "`>>`"),
+            Token::UnsignedShiftRight => write!(f, "`>>>`"),
+            Token::Comma => write!(f, "`,`"),
+            Token::StringLiteral(s) => write!(f, "string {:?}", s),
+            Token::Identifier(name) => write!(f, "identificador `{}`", name),
+            Token::Number(n) => write!(f, "número `{}`", n),
+            Token::Whitespace => write!(f, "espaço em branco"),
+            Token::Eof => write!(f, "fim do arquivo"),
+        }
+    }
 }
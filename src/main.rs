@@ -1,65 +1,51 @@
 mod ast;
+mod cli;
+mod diagnostics;
+mod driver;
 mod generator;
 mod lexer;
 mod parser;
-
-
-use generator::CodeGenerator;
-use lexer::Lexer;
-use parser::Parser;
-
-fn main() -> Result<(), std::io::Error> {
-    let source = r#"
-        let nome: string = "João";
-        console.log(nome);
-
-        if (true) {
-            let idade: number = 25;
-            console.log(idade);
-        } else {
-            console.log("Não é verdadeiro");
+mod resolver;
+mod typeck;
+
+use cli::Command;
+use diagnostics::{Diagnostic, Emitter};
+use std::env;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("erro: {}\n\n{}", error, cli::USAGE);
+            return ExitCode::from(2);
         }
+    };
 
-        let contador: number = 0;
-        while (contador < 5) {
-            console.log("Contador é " + contador);
-            contador = contador + 1;
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
         }
+        Command::Build(options) => match driver::build(&options) {
+            Ok(output_dir) => {
+                let output_dir = output_dir.display();
+                println!("Código Rust gerado com sucesso em {}/src/main.rs", output_dir);
+                println!("\nPara executar o código gerado:");
+                println!("1. cd {}", output_dir);
+                println!("2. cargo run");
+                ExitCode::SUCCESS
+            }
+            Err(error) => report(&error, &Emitter::new(options.color)),
+        },
+        Command::Run(options) => match driver::run(&options) {
+            Ok(code) => ExitCode::from(code),
+            Err(error) => report(&error, &Emitter::new(options.build.color)),
+        },
+    }
+}
 
-        const nomes: string[] = ["Jose", "Juan", "Guilherme", "Vinicius"];
-        console.log("Array de nomes:", nomes);
-
-        console.log("Operação:  ( 1 + 2 ) * 3");
-        let resultado1: number = (1 + 2) * 3;
-        console.log("Resultado:", resultado1);
-
-        console.log("Operação: 10 / ( 2 + 3 )");
-        let resultado2: number = 10 / (2 + 3);
-        console.log("Resultado:", resultado2);
-
-        console.log("Operação: ( 2 + 3 ) * ( 4 - 1 )");
-        let resultado3: number = (2 + 3) * (4 - 1);
-        console.log("Resultado:", resultado3);
-    "#;
-
-    println!("{}", source);
-    let lexer = Lexer::new(source);
-    let tokens = lexer.get_tokens();
-
-    let mut parser = Parser::new(tokens);
-    let statements = parser.parse();
-
-    // println!("Análise sintática concluída. Gerando código Rust...");
-    // for stmt in &statements {
-    //     println!("{:?}", stmt);
-    // }
-
-    let generator = CodeGenerator::new("dist/rust");
-    generator.generate(statements)?;
-
-    println!("Código Rust gerado com sucesso em dist/rust/src/main.rs");
-    println!("\nPara executar o código gerado:");
-    println!("1. cd dist/rust");
-    println!("2. cargo run");
-    Ok(())
+fn report(error: &driver::DriverError, emitter: &Emitter) -> ExitCode {
+    emitter.emit(&Diagnostic::error(error.to_string()), None);
+    ExitCode::from(error.exit_code())
 }
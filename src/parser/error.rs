@@ -0,0 +1,94 @@
+use crate::ast::UnaryOperator;
+use crate::diagnostics::{Diagnostic, ToDiagnostic};
+use crate::lexer::Token;
+use logos::Span;
+use std::fmt;
+use thiserror::Error;
+
+// O que o parser esperava encontrar no ponto do erro
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    Token(Token),
+    Expression,
+    Identifier,
+    Statement,
+    Type,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "{}", token),
+            Expected::Expression => write!(f, "uma expressão"),
+            Expected::Identifier => write!(f, "um identificador"),
+            Expected::Statement => write!(f, "uma declaração"),
+            Expected::Type => write!(f, "um tipo"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParseError {
+    #[error("esperava-se {expected}, mas foi encontrado {found}")]
+    UnexpectedToken {
+        expected: Expected,
+        found: Token,
+        span: Span,
+    },
+    #[error("tipo desconhecido `{name}`")]
+    UnknownType { name: String, span: Span },
+    #[error("lado esquerdo da atribuição não é um identificador")]
+    InvalidAssignmentTarget { span: Span },
+    #[error("`return` fora de uma função")]
+    ReturnOutsideFunction { span: Span },
+    #[error("{keyword} fora de um laço")]
+    OutsideLoop { keyword: Token, span: Span },
+    #[error("a constante `{name}` precisa de um valor inicial")]
+    MissingConstInitializer { name: String, span: Span },
+    #[error("operador unário `{operator}` antes de `**` é ambíguo")]
+    UnaryBeforeExponent { operator: UnaryOperator, span: Span },
+}
+
+impl ParseError {
+    pub fn span(&self) -> &Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnknownType { span, .. }
+            | ParseError::InvalidAssignmentTarget { span }
+            | ParseError::ReturnOutsideFunction { span }
+            | ParseError::OutsideLoop { span, .. }
+            | ParseError::MissingConstInitializer { span, .. }
+            | ParseError::UnaryBeforeExponent { span, .. } => span,
+        }
+    }
+}
+
+impl ToDiagnostic for ParseError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.to_string());
+        match self {
+            ParseError::UnexpectedToken { expected, span, .. } => {
+                diagnostic.with_label(span.clone(), format!("esperava-se {}", expected))
+            }
+            ParseError::UnknownType { span, .. } => diagnostic
+                .with_label(span.clone(), "tipo não suportado")
+                .with_note("os tipos suportados são `string`, `number`, `boolean` e arrays como `number[]`"),
+            ParseError::InvalidAssignmentTarget { span } => {
+                diagnostic.with_label(span.clone(), "não é possível atribuir a esta expressão")
+            }
+            ParseError::ReturnOutsideFunction { span } => diagnostic
+                .with_label(span.clone(), "só é permitido dentro do corpo de uma função"),
+            ParseError::OutsideLoop { span, .. } => diagnostic
+                .with_label(span.clone(), "só é permitido dentro de `for` ou `while`"),
+            ParseError::MissingConstInitializer { name, span } => diagnostic
+                .with_label(span.clone(), format!("inicialize com `{} = ...`", name))
+                .with_note("use `let` para declarar uma variável que recebe o valor depois"),
+            ParseError::UnaryBeforeExponent { operator, span } => diagnostic
+                .with_label(span.clone(), "coloque esta expressão entre parênteses")
+                .with_note(format!(
+                    "escreva `({}a) ** b` ou `{}(a ** b)`",
+                    operator, operator
+                )),
+        }
+    }
+}
@@ -1,344 +1,828 @@
-use crate::ast::{BinaryOperator, Expression, Statement, Type};
+mod error;
+pub use error::{Expected, ParseError};
+
+use crate::ast::{
+    ArrowBody, BinaryOperator, ConsoleMethod, Expr, Expression, Ident, Parameter, Spanned,
+    Statement, Stmt, Type, UnaryOperator, UpdateOperator,
+};
 use crate::lexer::Token;
 use logos::Span;
 
+type ParseResult<T> = Result<T, ParseError>;
+
 pub struct Parser {
     tokens: Vec<(Token, Span)>,
     current: usize,
+    errors: Vec<ParseError>,
+    // Quantas funções envolvem o ponto atual da análise
+    function_depth: usize,
+    // Quantos laços envolvem o ponto atual, dentro da função atual
+    loop_depth: usize,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<(Token, Span)>) -> Self {
-        Parser { tokens, current: 0 }
+        Parser {
+            tokens,
+            current: 0,
+            errors: Vec::new(),
+            function_depth: 0,
+            loop_depth: 0,
+        }
     }
 
-    pub fn parse(&mut self) -> Vec<Statement> {
+    // Analisa o arquivo inteiro mesmo na presença de erros, devolvendo as
+    // declarações que puderam ser reconhecidas junto com todos os erros.
+    pub fn parse_partial(&mut self) -> (Vec<Stmt>, Vec<ParseError>) {
         let mut statements = Vec::new();
         while !self.is_at_end() {
-            if let Some(stmt) = self.parse_statement() {
+            if let Some(stmt) = self.parse_statement_or_recover() {
                 statements.push(stmt);
             }
         }
-        statements
+        (statements, std::mem::take(&mut self.errors))
     }
 
-    fn parse_statement(&mut self) -> Option<Statement> {
-        match self.peek() {
-            Token::ConsoleLog => self.parse_console_log(),
-            Token::Let | Token::Const => self.parse_variable_declaration(),
-            Token::If => self.parse_if_statement(),
-            Token::While => self.parse_while_statement(),
-            Token::Identifier(_) => {
-                if self.lookahead_is(Token::Equal) {
-                    self.parse_assignment()
-                } else {
-                    // Se não for atribuição, evite loop consumindo o token (ex: expressão ou erro)
+    // Em caso de erro, registra-o e descarta tokens até um ponto seguro
+    // para continuar a análise (modo pânico).
+    fn parse_statement_or_recover(&mut self) -> Option<Stmt> {
+        let start = self.current;
+        match self.parse_statement() {
+            Ok(stmt) => Some(stmt),
+            Err(error) => {
+                self.errors.push(error);
+                // Garante progresso quando o erro ocorre no primeiro token
+                if self.current == start {
                     self.advance();
-                    None
                 }
-            }
-            _ => {
-                // Avança para evitar loop infinito
-                self.advance();
+                self.synchronize();
                 None
             }
         }
     }
 
+    // Para depois de um `;`, ou antes de um `}` ou de uma palavra-chave que
+    // inicia uma declaração. Blocos abertos durante o descarte são pulados
+    // por inteiro para não gerar erros em cascata.
+    fn synchronize(&mut self) {
+        let mut depth = 0usize;
+        while !self.is_at_end() {
+            match self.peek() {
+                Token::OpenBrace => depth += 1,
+                Token::CloseBrace if depth == 0 => return,
+                Token::CloseBrace => depth -= 1,
+                Token::Semicolon if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                Token::Let
+                | Token::Const
+                | Token::If
+                | Token::While
+                | Token::For
+                | Token::Break
+                | Token::Continue
+                | Token::Function
+                | Token::Return
+                | Token::Console(_)
+                    if depth == 0 =>
+                {
+                    return
+                }
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    fn parse_statement(&mut self) -> ParseResult<Stmt> {
+        let start = self.current_span().start;
+        let stmt = match self.peek() {
+            Token::Console(method) => self.parse_console_log(method),
+            Token::Let | Token::Const => self.parse_variable_declaration(),
+            Token::If => self.parse_if_statement(),
+            Token::While => self.parse_while_statement(),
+            Token::For => self.parse_for_statement(),
+            Token::Break | Token::Continue => self.parse_loop_control(),
+            Token::Function => self.parse_function_declaration(),
+            Token::Return => self.parse_return_statement(),
+            Token::Identifier(_)
+                if self.lookahead().and_then(assignment_operator).is_some() =>
+            {
+                self.parse_assignment()
+            }
+            Token::Identifier(_) | Token::PlusPlus | Token::MinusMinus => {
+                self.parse_expression_statement()
+            }
+            _ => Err(self.error_at_current(Expected::Statement)),
+        }?;
+        Ok(Spanned::new(stmt, self.span_from(start)))
+    }
 
-    fn parse_assignment_expression(&mut self) -> Option<Expression> {
-        let expr = self.parse_binary_expression(0)?; // agora com precedência
+    fn parse_assignment_expression(&mut self) -> ParseResult<Expr> {
+        let expr = self.parse_conditional()?;
 
-        if self.lookahead_is(Token::Equal) {
-            if let Expression::Identifier(name) = expr {
-                self.advance(); // consome '='
+        if let Some(op) = assignment_operator(&self.peek()) {
+            if let Expression::Identifier(name) = expr.node {
+                self.advance(); // consome '=' ou o operador composto
                 let value = self.parse_assignment_expression()?; // Recursivo p/ associatividade direita
-                return Some(Expression::Assignment {
-                    name,
-                    value: Box::new(value),
-                });
+                let span = expr.span.start..value.span.end;
+                return Ok(Spanned::new(
+                    Expression::Assignment {
+                        name: Spanned::new(name, expr.span),
+                        op,
+                        value: Box::new(value),
+                    },
+                    span,
+                ));
             } else {
-                eprintln!("Erro: lado esquerdo da atribuição não é um identificador.");
-                return None;
+                return Err(ParseError::InvalidAssignmentTarget { span: expr.span });
             }
         }
 
-        Some(expr)
+        Ok(expr)
+    }
+
+    // `a ? b : c`, abaixo do `||`; os ramos podem conter outros condicionais
+    fn parse_conditional(&mut self) -> ParseResult<Expr> {
+        let condition = self.parse_binary_expression(0)?; // agora com precedência
+        if !self.match_token(Token::Question) {
+            return Ok(condition);
+        }
+
+        let consequent = self.parse_assignment_expression()?;
+        self.expect(Token::Colon)?;
+        let alternate = self.parse_assignment_expression()?;
+
+        let span = condition.span.start..alternate.span.end;
+        Ok(Spanned::new(
+            Expression::Conditional {
+                condition: Box::new(condition),
+                consequent: Box::new(consequent),
+                alternate: Box::new(alternate),
+            },
+            span,
+        ))
+    }
+
+    fn parse_console_log(&mut self, method: ConsoleMethod) -> ParseResult<Statement> {
+        let stmt = self.parse_console_call(method)?;
+        self.expect(Token::Semicolon)?;
+        Ok(stmt)
     }
 
-    fn parse_console_log(&mut self) -> Option<Statement> {
-        self.advance(); // Consume 'console.log'
+    // `console.log(...)` sem o `;`, para uso também como corpo de arrow functions
+    fn parse_console_call(&mut self, method: ConsoleMethod) -> ParseResult<Statement> {
+        self.advance(); // Consume 'console.log', 'console.error' etc.
         self.expect(Token::OpenParen)?;
 
         let mut args = Vec::new();
 
-        while self.peek() != Token::CloseParen {
+        while !self.check(Token::CloseParen) {
             let expr = self.parse_expression()?;
             args.push(expr);
 
-        if self.peek() == Token::Comma {
-            self.advance();
-        } else {
-            break;
+            if !self.match_token(Token::Comma) {
+                break;
+            }
         }
-    }
 
-    self.expect(Token::CloseParen)?;
-    self.expect(Token::Semicolon)?;
+        self.expect(Token::CloseParen)?;
 
-    Some(Statement::ConsoleLog(args))
-}
+        Ok(Statement::Console { method, args })
+    }
 
+    fn parse_variable_declaration(&mut self) -> ParseResult<Statement> {
+        let is_const = matches!(self.advance(), Token::Const); // ou Let
+        let name = self.expect_identifier()?;
 
-    fn parse_variable_declaration(&mut self) -> Option<Statement> {
-        let _is_const = matches!(self.advance(), Token::Const); // ou Let
-        let name = if let Token::Identifier(name) = self.advance() {
-            name
+        let type_annotation = if self.match_token(Token::Colon) {
+            Some(self.parse_type()?)
         } else {
-            return None;
+            None
         };
 
-        self.expect(Token::Colon)?;
-        let type_annotation = self.parse_type()?;
-
         let value = if self.match_token(Token::Equal) {
-            let expr = self.parse_expression()?;
-            self.expect(Token::Semicolon)?;
-            Some(expr)
+            Some(self.parse_expression()?)
         } else {
-            self.expect(Token::Semicolon)?; // termina com ;
             None
         };
 
-        Some(Statement::VariableDeclaration {
+        // Sem anotação, o tipo é inferido depois pelo verificador de tipos
+        if is_const && value.is_none() {
+            return Err(ParseError::MissingConstInitializer {
+                name: name.node,
+                span: name.span,
+            });
+        }
+
+        self.expect(Token::Semicolon)?; // termina com ;
+
+        Ok(Statement::VariableDeclaration {
             name,
+            is_const,
             type_annotation,
             value,
         })
     }
 
-
-    fn parse_if_statement(&mut self) -> Option<Statement> {
+    fn parse_if_statement(&mut self) -> ParseResult<Statement> {
         self.advance(); // Consume 'if'
         self.expect(Token::OpenParen)?;
         let condition = self.parse_expression()?;
         self.expect(Token::CloseParen)?;
-        self.expect(Token::OpenBrace)?;
-
-        let mut then_branch = Vec::new();
-        while !self.check(Token::CloseBrace) && !self.is_at_end() {
-            if let Some(stmt) = self.parse_statement() {
-                then_branch.push(stmt);
-            }
-        }
-        self.expect(Token::CloseBrace)?;
+        let then_branch = self.parse_block()?;
 
         let else_branch = if self.match_token(Token::Else) {
-            self.expect(Token::OpenBrace)?;
-            let mut else_statements = Vec::new();
-            while !self.check(Token::CloseBrace) && !self.is_at_end() {
-                if let Some(stmt) = self.parse_statement() {
-                    else_statements.push(stmt);
-                }
-            }
-            self.expect(Token::CloseBrace)?;
-            Some(else_statements)
+            Some(self.parse_block()?)
         } else {
             None
         };
 
-        Some(Statement::IfStatement {
+        Ok(Statement::IfStatement {
             condition,
             then_branch,
             else_branch,
         })
     }
 
-    fn parse_while_statement(&mut self) -> Option<Statement> {
+    fn parse_while_statement(&mut self) -> ParseResult<Statement> {
         self.advance(); // Consume 'while'
         self.expect(Token::OpenParen)?;
         let condition = self.parse_expression()?;
         self.expect(Token::CloseParen)?;
+        let body = self.parse_loop_body()?;
+
+        Ok(Statement::WhileStatement { condition, body })
+    }
+
+    fn parse_for_statement(&mut self) -> ParseResult<Statement> {
+        self.advance(); // Consume 'for'
+        self.expect(Token::OpenParen)?;
+
+        if self.is_for_of() {
+            return self.parse_for_of_rest();
+        }
+
+        // A inicialização é uma declaração completa e já consome o `;`
+        let init = match self.peek() {
+            Token::Semicolon => {
+                self.advance();
+                None
+            }
+            Token::Let | Token::Const | Token::Identifier(_) => {
+                Some(Box::new(self.parse_statement()?))
+            }
+            _ => return Err(self.error_at_current(Expected::Statement)),
+        };
+
+        let condition = if self.check(Token::Semicolon) {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+        self.expect(Token::Semicolon)?;
+
+        let update = if self.check(Token::CloseParen) {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+        self.expect(Token::CloseParen)?;
+
+        let body = self.parse_loop_body()?;
+
+        Ok(Statement::ForStatement {
+            init,
+            condition,
+            update,
+            body,
+        })
+    }
+
+    // `let x of` / `const x of` logo após o `(` do `for`
+    fn is_for_of(&self) -> bool {
+        let token_at = |offset: usize| self.tokens.get(self.current + offset).map(|(token, _)| token);
+        matches!(token_at(0), Some(Token::Let | Token::Const))
+            && matches!(token_at(1), Some(Token::Identifier(_)))
+            && matches!(token_at(2), Some(Token::Identifier(word)) if word == "of")
+    }
+
+    // Restante de `for (const item of array) { ... }`, a partir do `const`
+    fn parse_for_of_rest(&mut self) -> ParseResult<Statement> {
+        let is_const = matches!(self.advance(), Token::Const); // ou Let
+        let variable = self.expect_identifier()?;
+        self.advance(); // Consume 'of'
+        let iterable = self.parse_expression()?;
+        self.expect(Token::CloseParen)?;
+        let body = self.parse_loop_body()?;
+
+        Ok(Statement::ForOfStatement {
+            variable,
+            is_const,
+            iterable,
+            body,
+        })
+    }
+
+    fn parse_loop_body(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.loop_depth += 1;
+        let body = self.parse_block();
+        self.loop_depth -= 1;
+        body
+    }
+
+    fn parse_loop_control(&mut self) -> ParseResult<Statement> {
+        let span = self.current_span();
+        let keyword = self.advance();
+        if self.loop_depth == 0 {
+            return Err(ParseError::OutsideLoop { keyword, span });
+        }
+        self.expect(Token::Semicolon)?;
+
+        Ok(match keyword {
+            Token::Break => Statement::Break,
+            _ => Statement::Continue,
+        })
+    }
+
+    // Corpo de uma função ou arrow function: `break`/`continue` não
+    // atravessam a fronteira da função.
+    fn parse_function_body(&mut self) -> ParseResult<Vec<Stmt>> {
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        self.function_depth += 1;
+        let body = self.parse_block();
+        self.function_depth -= 1;
+        self.loop_depth = loop_depth;
+        body
+    }
+
+    fn parse_function_declaration(&mut self) -> ParseResult<Statement> {
+        self.advance(); // Consume 'function'
+        let name = self.expect_identifier()?;
+
+        let params = self.parse_parameters(true)?;
+
+        let return_type = if self.match_token(Token::Colon) {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        let body = self.parse_function_body()?;
+
+        Ok(Statement::FunctionDeclaration {
+            name,
+            params,
+            return_type,
+            body,
+        })
+    }
+
+    // Lê `(a: tipo, b: tipo)`; os tipos só podem faltar em arrow functions
+    fn parse_parameters(&mut self, require_types: bool) -> ParseResult<Vec<Parameter>> {
+        self.expect(Token::OpenParen)?;
+        let mut params = Vec::new();
+        while !self.check(Token::CloseParen) {
+            let name = self.expect_identifier()?;
+            let type_annotation = if require_types || self.check(Token::Colon) {
+                self.expect(Token::Colon)?;
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            params.push(Parameter {
+                name,
+                type_annotation,
+            });
+
+            if !self.match_token(Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::CloseParen)?;
+        Ok(params)
+    }
+
+    fn parse_return_statement(&mut self) -> ParseResult<Statement> {
+        let span = self.current_span();
+        self.advance(); // Consume 'return'
+        if self.function_depth == 0 {
+            return Err(ParseError::ReturnOutsideFunction { span });
+        }
+
+        let value = if self.check(Token::Semicolon) {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+        self.expect(Token::Semicolon)?;
+
+        Ok(Statement::Return(value))
+    }
+
+    fn parse_expression_statement(&mut self) -> ParseResult<Statement> {
+        let expr = self.parse_expression()?;
+        self.expect(Token::Semicolon)?;
+        Ok(Statement::Expression(expr))
+    }
+
+    // Lê `{ declarações }`
+    fn parse_block(&mut self) -> ParseResult<Vec<Stmt>> {
         self.expect(Token::OpenBrace)?;
 
-        let mut body = Vec::new();
+        let mut statements = Vec::new();
         while !self.check(Token::CloseBrace) && !self.is_at_end() {
-            if let Some(stmt) = self.parse_statement() {
-                body.push(stmt);
+            if let Some(stmt) = self.parse_statement_or_recover() {
+                statements.push(stmt);
             }
         }
         self.expect(Token::CloseBrace)?;
 
-        Some(Statement::WhileStatement { condition, body })
+        Ok(statements)
     }
 
-    fn parse_assignment(&mut self) -> Option<Statement> {
+    fn parse_assignment(&mut self) -> ParseResult<Statement> {
         // Consome o identificador
-        let name = if let Token::Identifier(name) = self.advance() {
-            name
-        } else {
-            return None;
-        };
+        let name = self.expect_identifier()?;
 
-        // Consome o '='
-        self.expect(Token::Equal)?;
+        // Consome o '=' ou o operador composto, como '+='
+        let op = assignment_operator(&self.advance()).flatten();
 
-        // Pega a expressão depois do '='
+        // Pega a expressão depois do operador
         let value = self.parse_expression()?;
 
         // Consome o ';'
         self.expect(Token::Semicolon)?;
 
-        Some(Statement::Assignment { name, value })
+        Ok(Statement::Assignment { name, op, value })
     }
 
-
-    fn parse_expression(&mut self) -> Option<Expression> {
+    fn parse_expression(&mut self) -> ParseResult<Expr> {
         self.parse_assignment_expression()
     }
 
+    fn parse_binary_expression(&mut self, min_prec: u8) -> ParseResult<Expr> {
+        let mut left = self.parse_unary()?;
 
-    fn parse_binary_expression(&mut self, min_prec: u8) -> Option<Expression> {
-    let mut left = self.parse_primary()?;
-
-    loop {
-            let (prec, op) = match self.peek_operator_with_precedence() {
-                Some(p) => p,
-                None => break,
-            };
-
+        while let Some((prec, op)) = self.peek_operator_with_precedence() {
             if prec < min_prec {
                 break;
             }
 
             self.advance(); // consume operador
 
-            // Tentar com a mesma precedência para associatividade à esquerda
-            let right = self.parse_binary_expression(prec + 1)?;
-            left = Expression::BinaryOp {
-                left: Box::new(left),
-                op,
-                right: Box::new(right),
-            };
+            // Precedência maior à direita deixa o operador associativo à esquerda
+            let right_prec = if op.is_right_associative() { prec } else { prec + 1 };
+            let right = self.parse_binary_expression(right_prec)?;
+            let span = left.span.start..right.span.end;
+
+            left = Spanned::new(
+                Expression::BinaryOp {
+                    left: Box::new(left),
+                    op,
+                    right: Box::new(right),
+                },
+                span,
+            );
         }
 
-        Some(left)
+        Ok(left)
     }
 
     fn peek_operator_with_precedence(&self) -> Option<(u8, BinaryOperator)> {
-        match self.peek() {
-            Token::Plus => Some((1, BinaryOperator::Add)),
-            Token::Minus => Some((1, BinaryOperator::Subtract)),
-            Token::Star => Some((2, BinaryOperator::Multiply)),
-            Token::Slash => Some((2, BinaryOperator::Divide)),
-            Token::LessThan => Some((0, BinaryOperator::LessThan)),
-            Token::GreaterThan => Some((0, BinaryOperator::GreaterThan)),
-            _ => None,
+        let op = match self.peek() {
+            Token::Plus => BinaryOperator::Add,
+            Token::Minus => BinaryOperator::Subtract,
+            Token::Star => BinaryOperator::Multiply,
+            Token::Slash => BinaryOperator::Divide,
+            Token::Percent => BinaryOperator::Modulo,
+            Token::StarStar => BinaryOperator::Exponent,
+            Token::Ampersand => BinaryOperator::BitAnd,
+            Token::Pipe => BinaryOperator::BitOr,
+            Token::Caret => BinaryOperator::BitXor,
+            Token::ShiftLeft => BinaryOperator::ShiftLeft,
+            Token::ShiftRight => BinaryOperator::ShiftRight,
+            Token::UnsignedShiftRight => BinaryOperator::UnsignedShiftRight,
+            Token::LessThan => BinaryOperator::LessThan,
+            Token::GreaterThan => BinaryOperator::GreaterThan,
+            Token::LessEqual => BinaryOperator::LessEqual,
+            Token::GreaterEqual => BinaryOperator::GreaterEqual,
+            Token::EqualEqual => BinaryOperator::Equal,
+            Token::NotEqual => BinaryOperator::NotEqual,
+            Token::StrictEqual => BinaryOperator::StrictEqual,
+            Token::StrictNotEqual => BinaryOperator::StrictNotEqual,
+            Token::AndAnd => BinaryOperator::And,
+            Token::OrOr => BinaryOperator::Or,
+            _ => return None,
+        };
+        Some((op.precedence(), op))
+    }
+
+    // Operadores prefixados, que ligam mais forte que qualquer operador binário
+    fn parse_unary(&mut self) -> ParseResult<Expr> {
+        let start = self.current_span().start;
+        if let Some(op) = update_operator(&self.peek()) {
+            self.advance();
+            let operand = self.parse_unary()?;
+            return update_expression(operand, op, true, self.span_from(start));
+        }
+
+        let op = match self.peek() {
+            Token::Bang => UnaryOperator::Not,
+            Token::Minus => UnaryOperator::Negate,
+            Token::Tilde => UnaryOperator::BitNot,
+            _ => return self.parse_postfix(),
+        };
+        self.advance();
+
+        let operand = self.parse_unary()?;
+
+        // Como no JavaScript, `-a ** b` é ambíguo e exige parênteses
+        if self.check(Token::StarStar) {
+            return Err(ParseError::UnaryBeforeExponent {
+                operator: op,
+                span: self.span_from(start),
+            });
+        }
+        Ok(Spanned::new(
+            Expression::Unary {
+                op,
+                operand: Box::new(operand),
+            },
+            self.span_from(start),
+        ))
+    }
+
+    // `x++` e `x--`
+    fn parse_postfix(&mut self) -> ParseResult<Expr> {
+        let expr = self.parse_call()?;
+        match update_operator(&self.peek()) {
+            Some(op) => {
+                self.advance();
+                let span = self.span_from(expr.span.start);
+                update_expression(expr, op, false, span)
+            }
+            None => Ok(expr),
         }
     }
 
-    fn parse_primary(&mut self) -> Option<Expression> {
-    match self.advance() {
-        Token::StringLiteral(s) => Some(Expression::StringLiteral(s)),
-        Token::Number(n) => Some(Expression::NumberLiteral(n)),
-        Token::Identifier(name) => Some(Expression::Identifier(name)),
-        Token::OpenBracket => {
-            let mut elements = Vec::new();
-            while !self.check(Token::CloseBracket) && !self.is_at_end() {
-                if let Some(expr) = self.parse_expression() {
-                    elements.push(expr);
-                    if !self.match_token(Token::Comma) {
+    // Chamadas como `f(a, b)`, possivelmente encadeadas: `f(a)(b)`
+    fn parse_call(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.parse_primary()?;
+
+        while self.match_token(Token::OpenParen) {
+            let mut args = Vec::new();
+            while !self.check(Token::CloseParen) {
+                args.push(self.parse_expression()?);
+                if !self.match_token(Token::Comma) {
+                    break;
+                }
+            }
+            self.expect(Token::CloseParen)?;
+
+            let span = self.span_from(expr.span.start);
+            expr = Spanned::new(
+                Expression::Call {
+                    callee: Box::new(expr),
+                    args,
+                },
+                span,
+            );
+        }
+
+        Ok(expr)
+    }
+
+    // `(a: number, b: number): number => a + b`, `(x) => { ... }` ou `x => x * 2`
+    fn parse_arrow_function(&mut self) -> ParseResult<Expr> {
+        let start = self.current_span().start;
+
+        let params = if let Token::Identifier(_) = self.peek() {
+            vec![Parameter {
+                name: self.expect_identifier()?,
+                type_annotation: None,
+            }]
+        } else {
+            self.parse_parameters(false)?
+        };
+
+        let return_type = if self.match_token(Token::Colon) {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        self.expect(Token::Arrow)?;
+
+        let body = if self.check(Token::OpenBrace) {
+            ArrowBody::Block(self.parse_function_body()?)
+        } else if let Token::Console(method) = self.peek() {
+            // `() => console.log(x)` equivale a um bloco com a chamada
+            let start = self.current_span().start;
+            let stmt = self.parse_console_call(method)?;
+            ArrowBody::Block(vec![Spanned::new(stmt, self.span_from(start))])
+        } else {
+            ArrowBody::Expression(Box::new(self.parse_expression()?))
+        };
+
+        Ok(Spanned::new(
+            Expression::ArrowFunction {
+                params,
+                return_type,
+                body,
+            },
+            self.span_from(start),
+        ))
+    }
+
+    // Decide, sem consumir tokens, se o `(` ou identificador atual inicia uma
+    // arrow function: o `)` correspondente deve ser seguido de `=>` ou de
+    // uma anotação de tipo de retorno e então `=>`.
+    fn is_arrow_function_start(&self) -> bool {
+        let token_at = |index: usize| {
+            self.tokens
+                .get(index)
+                .map_or(Token::Eof, |(token, _)| token.clone())
+        };
+
+        let mut index = self.current;
+        match token_at(index) {
+            Token::Identifier(_) => return token_at(index + 1) == Token::Arrow,
+            Token::OpenParen => {}
+            _ => return false,
+        }
+
+        let mut depth = 0usize;
+        loop {
+            match token_at(index) {
+                Token::OpenParen => depth += 1,
+                Token::CloseParen => {
+                    depth -= 1;
+                    if depth == 0 {
                         break;
                     }
-                } else {
-                    break;
                 }
+                Token::Eof => return false,
+                _ => {}
             }
-            self.expect(Token::CloseBracket)?;
-            Some(Expression::ArrayLiteral(elements))
+            index += 1;
         }
-        Token::OpenParen => {
-            let expr = self.parse_expression()?;       
-            self.expect(Token::CloseParen)?;           
-            Some(expr)                                 
+
+        index += 1;
+        match token_at(index) {
+            Token::Arrow => true,
+            Token::Colon => {
+                // Pula o tipo de retorno: nomes, `[]` e tipos de função entre parênteses
+                index += 1;
+                let mut depth = 0usize;
+                loop {
+                    match token_at(index) {
+                        Token::Arrow if depth == 0 => return true,
+                        Token::OpenParen => depth += 1,
+                        Token::CloseParen if depth > 0 => depth -= 1,
+                        Token::Identifier(_)
+                        | Token::OpenBracket
+                        | Token::CloseBracket
+                        | Token::Colon
+                        | Token::Comma
+                        | Token::Arrow => {}
+                        _ => return false,
+                    }
+                    index += 1;
+                }
+            }
+            _ => false,
         }
-        _ => None,
     }
-}
 
+    fn parse_primary(&mut self) -> ParseResult<Expr> {
+        if self.is_arrow_function_start() {
+            return self.parse_arrow_function();
+        }
 
-    fn parse_binary_operator(&mut self) -> Option<BinaryOperator> {
-        match self.peek() {
-            Token::Plus => {
+        let start = self.current_span().start;
+        let expr = match self.peek() {
+            Token::StringLiteral(s) => {
                 self.advance();
-                Some(BinaryOperator::Add)
+                Expression::StringLiteral(s)
             }
-            Token::Minus => {
+            Token::Number(n) => {
                 self.advance();
-                Some(BinaryOperator::Subtract)
+                Expression::NumberLiteral(n)
             }
-            Token::Star => {
+            Token::True => {
                 self.advance();
-                Some(BinaryOperator::Multiply)
+                Expression::BooleanLiteral(true)
             }
-            Token::Slash => {
+            Token::False => {
                 self.advance();
-                Some(BinaryOperator::Divide)
+                Expression::BooleanLiteral(false)
             }
-            Token::LessThan => {
+            Token::Identifier(name) => {
                 self.advance();
-                Some(BinaryOperator::LessThan)
+                Expression::Identifier(name)
             }
-            Token::GreaterThan => {
+            Token::OpenBracket => {
                 self.advance();
-                Some(BinaryOperator::GreaterThan)
+                let mut elements = Vec::new();
+                while !self.check(Token::CloseBracket) && !self.is_at_end() {
+                    elements.push(self.parse_expression()?);
+                    if !self.match_token(Token::Comma) {
+                        break;
+                    }
+                }
+                self.expect(Token::CloseBracket)?;
+                Expression::ArrayLiteral(elements)
             }
-            _ => None,
-        }
+            Token::OpenParen => {
+                // Parênteses só agrupam; o span é o da expressão interna
+                self.advance();
+                let expr = self.parse_expression()?;
+                self.expect(Token::CloseParen)?;
+                return Ok(expr);
+            }
+            _ => return Err(self.error_at_current(Expected::Expression)),
+        };
+        Ok(Spanned::new(expr, self.span_from(start)))
     }
 
-    fn parse_type(&mut self) -> Option<Type> {
-    match self.advance() {
-        Token::Identifier(name) => {
-            let base_type = match name.as_str() {
-                "string" => Type::String,
-                "number" => Type::Number,
-                "boolean" => Type::Boolean,
-                _ => return None,
-            };
+    fn parse_type(&mut self) -> ParseResult<Type> {
+        // Tipo de função: `(x: number) => number`
+        if self.check(Token::OpenParen) {
+            let params = self.parse_parameters(true)?;
+            self.expect(Token::Arrow)?;
+            let return_type = self.parse_type()?;
+            return Ok(Type::Function {
+                params: params
+                    .into_iter()
+                    .filter_map(|param| param.type_annotation)
+                    .collect(),
+                return_type: Box::new(return_type),
+            });
+        }
 
-            if self.check(Token::OpenBracket) {
-                self.advance();
-                self.expect(Token::CloseBracket)?;
-                Some(Type::Array(Box::new(base_type)))
-            } else {
-                Some(base_type)
-            }
+        let name = match self.peek() {
+            Token::Identifier(name) => name,
+            _ => return Err(self.error_at_current(Expected::Type)),
+        };
+        let span = self.current_span();
+        self.advance();
+
+        let base_type = match name.as_str() {
+            "string" => Type::String,
+            "number" => Type::Number,
+            "boolean" => Type::Boolean,
+            "void" => Type::Void,
+            _ => return Err(ParseError::UnknownType { name, span }),
+        };
+
+        if self.match_token(Token::OpenBracket) {
+            self.expect(Token::CloseBracket)?;
+            Ok(Type::Array(Box::new(base_type)))
+        } else {
+            Ok(base_type)
         }
-        _ => None,
     }
-}
-
 
     fn advance(&mut self) -> Token {
         if !self.is_at_end() {
             self.current += 1;
         }
-        self.previous().clone()
+        self.previous()
     }
 
     fn peek(&self) -> Token {
         self.tokens
             .get(self.current)
             .map(|t| t.0.clone())
-            .unwrap_or(Token::Semicolon)
+            .unwrap_or(Token::Eof)
     }
 
     fn previous(&self) -> Token {
         self.tokens
             .get(self.current.saturating_sub(1))
             .map(|t| t.0.clone())
-            .unwrap_or(Token::Semicolon)
+            .unwrap_or(Token::Eof)
+    }
+
+    // Span do token atual; no fim da entrada, uma posição logo após o último token
+    fn current_span(&self) -> Span {
+        match self.tokens.get(self.current) {
+            Some((_, span)) => span.clone(),
+            None => {
+                let end = self.tokens.last().map_or(0, |(_, span)| span.end);
+                end..end
+            }
+        }
+    }
+
+    // Span que vai de `start` até o fim do último token consumido
+    fn span_from(&self, start: usize) -> Span {
+        let end = self
+            .tokens
+            .get(self.current.saturating_sub(1))
+            .map_or(start, |(_, span)| span.end);
+        start..end.max(start)
     }
 
     fn check(&self, token: Token) -> bool {
@@ -358,12 +842,31 @@ impl Parser {
         }
     }
 
-    fn expect(&mut self, token: Token) -> Option<()> {
-        if self.check(token) {
+    fn expect(&mut self, token: Token) -> ParseResult<()> {
+        if self.check(token.clone()) {
             self.advance();
-            Some(())
+            Ok(())
         } else {
-            None
+            Err(self.error_at_current(Expected::Token(token)))
+        }
+    }
+
+    fn expect_identifier(&mut self) -> ParseResult<Ident> {
+        match self.peek() {
+            Token::Identifier(name) => {
+                let span = self.current_span();
+                self.advance();
+                Ok(Spanned::new(name, span))
+            }
+            _ => Err(self.error_at_current(Expected::Identifier)),
+        }
+    }
+
+    fn error_at_current(&self, expected: Expected) -> ParseError {
+        ParseError::UnexpectedToken {
+            expected,
+            found: self.peek(),
+            span: self.current_span(),
         }
     }
 
@@ -371,12 +874,49 @@ impl Parser {
         self.current >= self.tokens.len()
     }
 
-    fn lookahead_is(&self, expected: Token) -> bool {
-        if self.current + 1 >= self.tokens.len() {
-            return false;
-        }
-        std::mem::discriminant(&self.tokens[self.current + 1].0)
-            == std::mem::discriminant(&expected)
+    // Token seguinte ao atual
+    fn lookahead(&self) -> Option<&Token> {
+        self.tokens.get(self.current + 1).map(|(token, _)| token)
     }
+}
 
+// `Some(None)` para `=`, `Some(Some(op))` para atribuições compostas como `+=`
+fn assignment_operator(token: &Token) -> Option<Option<BinaryOperator>> {
+    match token {
+        Token::Equal => Some(None),
+        Token::PlusEqual => Some(Some(BinaryOperator::Add)),
+        Token::MinusEqual => Some(Some(BinaryOperator::Subtract)),
+        Token::StarEqual => Some(Some(BinaryOperator::Multiply)),
+        Token::SlashEqual => Some(Some(BinaryOperator::Divide)),
+        Token::PercentEqual => Some(Some(BinaryOperator::Modulo)),
+        _ => None,
+    }
+}
+
+fn update_operator(token: &Token) -> Option<UpdateOperator> {
+    match token {
+        Token::PlusPlus => Some(UpdateOperator::Increment),
+        Token::MinusMinus => Some(UpdateOperator::Decrement),
+        _ => None,
+    }
+}
+
+// `++` e `--` só se aplicam a variáveis
+fn update_expression(
+    operand: Expr,
+    op: UpdateOperator,
+    prefix: bool,
+    span: Span,
+) -> ParseResult<Expr> {
+    match operand.node {
+        Expression::Identifier(name) => Ok(Spanned::new(
+            Expression::Update {
+                name: Spanned::new(name, operand.span),
+                op,
+                prefix,
+            },
+            span,
+        )),
+        _ => Err(ParseError::InvalidAssignmentTarget { span: operand.span }),
+    }
 }
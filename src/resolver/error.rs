@@ -0,0 +1,179 @@
+use crate::diagnostics::{Diagnostic, ToDiagnostic};
+use logos::Span;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ResolveError {
+    #[error("a variável `{name}` não foi declarada")]
+    UndefinedVariable { name: String, span: Span },
+    #[error("a variável `{name}` foi usada antes de ser declarada")]
+    UseBeforeDeclaration {
+        name: String,
+        span: Span,
+        declaration: Option<Span>,
+    },
+    #[error("a variável `{name}` é usada antes de receber um valor")]
+    UnassignedVariable {
+        name: String,
+        span: Span,
+        declaration: Option<Span>,
+    },
+    #[error("`{name}` já foi declarada neste escopo")]
+    DuplicateDeclaration {
+        name: String,
+        span: Span,
+        // `None` quando a primeira declaração está em outro arquivo
+        previous: Option<Span>,
+    },
+    #[error("atribuição à variável `{name}`, que não foi declarada")]
+    UndeclaredAssignment { name: String, span: Span },
+    #[error("não é possível atribuir à constante `{name}`")]
+    AssignToConst {
+        name: String,
+        span: Span,
+        // `None` quando a constante foi declarada em outro arquivo
+        declaration: Option<Span>,
+    },
+    #[error("a função não pode usar `{name}`, declarada fora dela")]
+    CapturedByFunction {
+        name: String,
+        span: Span,
+        declaration: Option<Span>,
+    },
+    #[error(
+        "`{name}` não pode ser {} enquanto a closure `{closure}` a captura",
+        if *write { "alterada" } else { "usada" }
+    )]
+    CapturedByClosure {
+        name: String,
+        closure: String,
+        span: Span,
+        // O uso conflitante altera a variável
+        write: bool,
+        // Closure chamada ou criada no uso conflitante
+        by: Option<String>,
+        // Uso da closure depois do conflito
+        closure_use: Span,
+    },
+}
+
+impl ResolveError {
+    pub fn span(&self) -> &Span {
+        match self {
+            ResolveError::UndefinedVariable { span, .. }
+            | ResolveError::UseBeforeDeclaration { span, .. }
+            | ResolveError::UnassignedVariable { span, .. }
+            | ResolveError::DuplicateDeclaration { span, .. }
+            | ResolveError::UndeclaredAssignment { span, .. }
+            | ResolveError::AssignToConst { span, .. }
+            | ResolveError::CapturedByFunction { span, .. }
+            | ResolveError::CapturedByClosure { span, .. } => span,
+        }
+    }
+}
+
+impl ToDiagnostic for ResolveError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.to_string());
+        match self {
+            ResolveError::UndefinedVariable { span, .. } => {
+                diagnostic.with_label(span.clone(), "não encontrada neste escopo")
+            }
+            ResolveError::UseBeforeDeclaration {
+                span, declaration, ..
+            } => with_declaration(
+                diagnostic.with_label(span.clone(), "usada aqui"),
+                declaration,
+                "declarada só aqui",
+            ),
+            ResolveError::UnassignedVariable {
+                span, declaration, ..
+            } => with_declaration(
+                diagnostic
+                    .with_label(span.clone(), "pode ainda não ter um valor aqui")
+                    .with_note(
+                        "atribua um valor na declaração ou em todos os caminhos antes deste uso",
+                    ),
+                declaration,
+                "declarada sem valor aqui",
+            ),
+            ResolveError::DuplicateDeclaration {
+                name,
+                span,
+                previous,
+            } => with_declaration(
+                diagnostic
+                    .with_label(span.clone(), "declarada de novo aqui")
+                    .with_note(format!(
+                        "para alterar o valor, use uma atribuição: `{} = ...`",
+                        name
+                    )),
+                previous,
+                "declarada pela primeira vez aqui",
+            ),
+            ResolveError::UndeclaredAssignment { name, span } => diagnostic
+                .with_label(span.clone(), "não encontrada neste escopo")
+                .with_note(format!("declare-a antes com `let {}`", name)),
+            ResolveError::AssignToConst {
+                name,
+                span,
+                declaration,
+            } => with_declaration(
+                diagnostic
+                    .with_label(span.clone(), "atribuição a uma constante")
+                    .with_note(format!("declare com `let {}` para poder alterá-la", name)),
+                declaration,
+                "declarada com `const` aqui",
+            ),
+            ResolveError::CapturedByFunction {
+                span, declaration, ..
+            } => with_declaration(
+                diagnostic
+                    .with_label(span.clone(), "usada dentro de uma declaração `function`")
+                    .with_note(
+                        "passe o valor como parâmetro ou use uma arrow function, \
+                         como `const f = (x: number) => ...`",
+                    ),
+                declaration,
+                "declarada aqui",
+            ),
+            ResolveError::CapturedByClosure {
+                name,
+                closure,
+                span,
+                write,
+                by,
+                closure_use,
+            } => {
+                let access = if *write { "alterada" } else { "usada" };
+                let label = match by {
+                    Some(by) => format!("{} pela closure `{}` aqui", access, by),
+                    None => format!("{} aqui", access),
+                };
+                diagnostic
+                    .with_label(span.clone(), label)
+                    .with_secondary_label(
+                        closure_use.clone(),
+                        format!("`{}` é usada de novo aqui", closure),
+                    )
+                    .with_note(format!(
+                        "a closure pega `{}` emprestada até o seu último uso; passe o valor \
+                     como parâmetro da arrow function",
+                        name
+                    ))
+            }
+        }
+    }
+}
+
+// Aponta também para a declaração, quando ela está no mesmo arquivo
+fn with_declaration(
+    diagnostic: Diagnostic,
+    declaration: &Option<Span>,
+    message: &str,
+) -> Diagnostic {
+    match declaration {
+        Some(declaration) => diagnostic.with_secondary_label(declaration.clone(), message),
+        None => diagnostic,
+    }
+}
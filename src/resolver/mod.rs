@@ -0,0 +1,640 @@
+mod error;
+pub use error::ResolveError;
+
+use crate::ast::{ArrowBody, Expr, Expression, Ident, Parameter, Statement, Stmt};
+use logos::Span;
+use std::collections::{HashMap, HashSet};
+
+// Associa cada uso de um nome à declaração visível naquele ponto, seguindo
+// as regras de escopo de bloco do `let` e do `const`.
+pub struct Resolver {
+    scopes: Vec<HashMap<String, Declaration>>,
+    errors: Vec<ResolveError>,
+    // Quantas funções envolvem o ponto atual da análise
+    function_depth: usize,
+    // Índice do primeiro escopo de cada `function` que envolve o ponto atual;
+    // elas viram itens `fn` do Rust, que não capturam variáveis de fora.
+    function_scopes: Vec<usize>,
+    // Variáveis de fora usadas por cada arrow function que envolve o ponto
+    // atual, junto com a profundidade dela; `true` para as que ela altera
+    captures: Vec<(usize, HashMap<Capture, bool>)>,
+    // Capturas da última arrow function resolvida
+    last_captures: HashMap<Capture, bool>,
+    // Conta os usos de nomes, na ordem do código
+    uses: usize,
+}
+
+// Escopo e nome de uma variável capturada
+type Capture = (usize, String);
+
+// Uso de uma variável capturada, direto ou pela chamada de uma closure
+// (`closure`), que pode entrar em conflito com outra closure
+struct Access {
+    capture: Capture,
+    span: Span,
+    write: bool,
+    position: usize,
+    closure: Option<Capture>,
+}
+
+// Uso de uma variável capturada que o Rust não permite enquanto a closure
+// ainda pode ser chamada
+struct Conflict {
+    name: String,
+    span: Span,
+    write: bool,
+    position: usize,
+    // Closure que usou a variável, quando o uso foi uma chamada
+    closure: Option<String>,
+}
+
+struct Declaration {
+    is_const: bool,
+    // Declarada com `function`, e não como variável
+    is_function: bool,
+    // `None` quando a declaração veio de outro arquivo
+    span: Option<Span>,
+    // Falso entre o início do bloco e a declaração, quando o nome já existe
+    // mas ainda não pode ser usado
+    initialized: bool,
+    // Recebeu um valor em todos os caminhos até o ponto atual
+    assigned: bool,
+    function_depth: usize,
+    // Capturas da arrow function guardada nesta variável
+    captures: HashMap<Capture, bool>,
+    // Conflito com as capturas ainda sem um uso da closure depois dele
+    conflict: Option<Conflict>,
+    // Último uso da variável
+    last_use: Option<(usize, Span)>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver {
+            scopes: vec![HashMap::new()],
+            errors: Vec::new(),
+            function_depth: 0,
+            function_scopes: Vec::new(),
+            captures: Vec::new(),
+            last_captures: HashMap::new(),
+            uses: 0,
+        }
+    }
+
+    // Resolve as declarações de um arquivo. As declarações do topo continuam
+    // visíveis para os arquivos resolvidos em seguida.
+    pub fn resolve(&mut self, stmts: &[Stmt]) -> Result<(), Vec<ResolveError>> {
+        self.resolve_statements(stmts);
+        for declaration in self.scopes[0].values_mut() {
+            declaration.span = None;
+        }
+
+        // As declarações duplicadas são encontradas antes do restante do
+        // bloco; os erros são exibidos na ordem do código
+        let mut errors = std::mem::take(&mut self.errors);
+        errors.sort_by_key(|error| error.span().start);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn declare(&mut self, name: &Ident, is_const: bool, initialized: bool) {
+        self.declare_binding(name, is_const, false, initialized);
+    }
+
+    fn declare_binding(
+        &mut self,
+        name: &Ident,
+        is_const: bool,
+        is_function: bool,
+        initialized: bool,
+    ) {
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+        if let Some(previous) = scope.get(&name.node) {
+            self.errors.push(ResolveError::DuplicateDeclaration {
+                name: name.node.clone(),
+                span: name.span.clone(),
+                previous: previous.span.clone(),
+            });
+            return;
+        }
+        let declaration = Declaration {
+            is_const,
+            is_function,
+            span: Some(name.span.clone()),
+            initialized,
+            assigned: initialized,
+            function_depth: self.function_depth,
+            captures: HashMap::new(),
+            conflict: None,
+            last_use: None,
+        };
+        scope.insert(name.node.clone(), declaration);
+    }
+
+    fn declare_parameters(&mut self, params: &[Parameter]) {
+        for param in params {
+            self.declare(&param.name, false, true);
+        }
+    }
+
+    // Declara uma variável do bloco antes de resolvê-lo; ela só pode ser
+    // usada depois que a própria declaração for resolvida.
+    fn hoist(&mut self, stmt: &Stmt) {
+        match &stmt.node {
+            Statement::VariableDeclaration { name, is_const, .. } => {
+                self.declare(name, *is_const, false)
+            }
+            // Funções podem ser chamadas antes da declaração, como no JavaScript
+            Statement::FunctionDeclaration { name, .. } => {
+                self.declare_binding(name, false, true, true)
+            }
+            _ => {}
+        }
+    }
+
+    fn initialize(&mut self, name: &Ident, assigned: bool, captures: HashMap<Capture, bool>) {
+        let declaration = self
+            .scopes
+            .last_mut()
+            .and_then(|scope| scope.get_mut(&name.node));
+        if let Some(declaration) = declaration {
+            declaration.initialized = true;
+            declaration.assigned = assigned;
+            declaration.captures = captures;
+        }
+    }
+
+    // Variáveis declaradas que ainda podem estar sem valor
+    fn unassigned(&self) -> HashSet<Capture> {
+        let mut unassigned = HashSet::new();
+        for (index, scope) in self.scopes.iter().enumerate() {
+            for (name, declaration) in scope {
+                if declaration.initialized && !declaration.assigned {
+                    unassigned.insert((index, name.clone()));
+                }
+            }
+        }
+        unassigned
+    }
+
+    // Depois de caminhos alternativos, das variáveis que estavam sem valor
+    // (`before`), só as de `unassigned` continuam sem valor
+    fn restore_unassigned(&mut self, before: &HashSet<Capture>, unassigned: &HashSet<Capture>) {
+        for capture in before {
+            if let Some(declaration) = self.scopes[capture.0].get_mut(&capture.1) {
+                declaration.assigned = !unassigned.contains(capture);
+            }
+        }
+    }
+
+    // Resolve caminhos alternativos a partir do mesmo estado; `None` para um
+    // caminho ausente, que não altera nada
+    fn resolve_branches(&mut self, branches: [Option<&[Stmt]>; 2]) {
+        let before = self.unassigned();
+        let mut unassigned = HashSet::new();
+        for branch in branches {
+            self.restore_unassigned(&before, &before);
+            match branch {
+                Some(stmts) => {
+                    self.resolve_block(stmts);
+                    // Um caminho que não chega ao fim não deixa nada sem valor
+                    if !diverges(stmts) {
+                        unassigned.extend(self.unassigned().intersection(&before).cloned());
+                    }
+                }
+                None => unassigned.extend(before.iter().cloned()),
+            }
+        }
+        self.restore_unassigned(&before, &unassigned);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Declaration> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn resolve_block(&mut self, stmts: &[Stmt]) {
+        self.scopes.push(HashMap::new());
+        self.resolve_statements(stmts);
+        self.scopes.pop();
+    }
+
+    fn resolve_statements(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.hoist(stmt);
+        }
+        for stmt in stmts {
+            self.resolve_statement(stmt);
+        }
+    }
+
+    fn resolve_statement(&mut self, stmt: &Stmt) {
+        match &stmt.node {
+            Statement::Console { args, .. } => {
+                for arg in args {
+                    self.resolve_expression(arg);
+                }
+            }
+            Statement::VariableDeclaration { name, value, .. } => {
+                let mut captures = HashMap::new();
+                if let Some(value) = value {
+                    self.resolve_expression(value);
+                    if matches!(value.node, Expression::ArrowFunction { .. }) {
+                        captures = std::mem::take(&mut self.last_captures);
+                    }
+                }
+                // Criar a closure já pega as variáveis emprestadas
+                if self.captures.is_empty() {
+                    self.uses += 1;
+                    for (capture, write) in &captures {
+                        self.check_access(Access {
+                            capture: capture.clone(),
+                            span: name.span.clone(),
+                            write: *write,
+                            position: self.uses,
+                            closure: Some((self.scopes.len() - 1, name.node.clone())),
+                        });
+                    }
+                }
+                self.initialize(name, value.is_some(), captures);
+            }
+            Statement::IfStatement {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expression(condition);
+                self.resolve_branches([Some(then_branch), else_branch.as_deref()]);
+            }
+            Statement::WhileStatement { condition, body } => {
+                let start = self.uses;
+                self.resolve_expression(condition);
+                // O corpo de um laço pode não ser executado nenhuma vez
+                self.resolve_branches([Some(body), None]);
+                self.check_loop(start);
+            }
+            Statement::ForStatement {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                // A variável do `init` pertence a um escopo que envolve o laço
+                self.scopes.push(HashMap::new());
+                if let Some(init) = init {
+                    self.hoist(init);
+                    self.resolve_statement(init);
+                }
+                let start = self.uses;
+                if let Some(condition) = condition {
+                    self.resolve_expression(condition);
+                }
+                let before = self.unassigned();
+                self.resolve_block(body);
+                if let Some(update) = update {
+                    self.resolve_expression(update);
+                }
+                self.restore_unassigned(&before, &before);
+                self.scopes.pop();
+                self.check_loop(start);
+            }
+            Statement::ForOfStatement {
+                variable,
+                is_const,
+                iterable,
+                body,
+            } => {
+                self.resolve_expression(iterable);
+                let start = self.uses;
+                self.scopes.push(HashMap::new());
+                self.declare(variable, *is_const, true);
+                self.resolve_branches([Some(body), None]);
+                self.scopes.pop();
+                self.check_loop(start);
+            }
+            Statement::Break | Statement::Continue | Statement::Return(None) => {}
+            Statement::Assignment { name, op, value } => {
+                self.resolve_expression(value);
+                self.resolve_assignment(name, op.is_some());
+            }
+            Statement::FunctionDeclaration { params, body, .. } => {
+                self.function_depth += 1;
+                self.function_scopes.push(self.scopes.len());
+                self.scopes.push(HashMap::new());
+                self.declare_parameters(params);
+                self.resolve_statements(body);
+                self.scopes.pop();
+                self.function_scopes.pop();
+                self.function_depth -= 1;
+            }
+            Statement::Return(Some(expr)) | Statement::Expression(expr) => {
+                self.resolve_expression(expr);
+            }
+        }
+    }
+
+    fn resolve_expression(&mut self, expr: &Expr) {
+        match &expr.node {
+            Expression::Assignment { name, op, value } => {
+                self.resolve_expression(value);
+                self.resolve_assignment(name, op.is_some());
+            }
+            Expression::Update { name, .. } => self.resolve_assignment(name, true),
+            Expression::BinaryOp { left, op, right } => {
+                self.resolve_expression(left);
+                // O lado direito de `&&` e `||` pode não ser avaliado
+                if op.is_logical() {
+                    let before = self.unassigned();
+                    self.resolve_expression(right);
+                    self.restore_unassigned(&before, &before);
+                } else {
+                    self.resolve_expression(right);
+                }
+            }
+            Expression::Unary { operand, .. } => self.resolve_expression(operand),
+            Expression::Conditional {
+                condition,
+                consequent,
+                alternate,
+            } => {
+                self.resolve_expression(condition);
+                let before = self.unassigned();
+                self.resolve_expression(consequent);
+                let mut unassigned = self.unassigned();
+                self.restore_unassigned(&before, &before);
+                self.resolve_expression(alternate);
+                unassigned.extend(self.unassigned());
+                self.restore_unassigned(&before, &unassigned);
+            }
+            Expression::ArrayLiteral(elements) => {
+                for element in elements {
+                    self.resolve_expression(element);
+                }
+            }
+            Expression::Call { callee, args } => {
+                self.resolve_expression(callee);
+                for arg in args {
+                    self.resolve_expression(arg);
+                }
+            }
+            Expression::ArrowFunction { params, body, .. } => {
+                self.function_depth += 1;
+                self.captures.push((self.function_depth, HashMap::new()));
+                self.scopes.push(HashMap::new());
+                self.declare_parameters(params);
+                match body {
+                    ArrowBody::Expression(expr) => self.resolve_expression(expr),
+                    ArrowBody::Block(stmts) => self.resolve_statements(stmts),
+                }
+                self.scopes.pop();
+                if let Some((_, captures)) = self.captures.pop() {
+                    self.last_captures = captures;
+                }
+                self.function_depth -= 1;
+            }
+            Expression::Identifier(name) => {
+                let name = Ident::new(name.clone(), expr.span.clone());
+                if self.resolve_use(&name, false).is_none() {
+                    self.errors.push(ResolveError::UndefinedVariable {
+                        name: name.node,
+                        span: name.span,
+                    });
+                }
+            }
+            Expression::StringLiteral(_)
+            | Expression::NumberLiteral(_)
+            | Expression::BooleanLiteral(_) => {}
+        }
+    }
+
+    // Declaração usada por `name`; `None` se o nome não foi declarado.
+    // `write` indica que o uso altera a variável.
+    fn resolve_use(&mut self, name: &Ident, write: bool) -> Option<&Declaration> {
+        let function_depth = self.function_depth;
+        let function_scope = self.function_scopes.last().copied().unwrap_or(0);
+        let (index, declaration) = self
+            .scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, scope)| Some((index, scope.get(&name.node)?)))?;
+
+        if index < function_scope && !declaration.is_function {
+            self.errors.push(ResolveError::CapturedByFunction {
+                name: name.node.clone(),
+                span: name.span.clone(),
+                declaration: declaration.span.clone(),
+            });
+        }
+
+        // Dentro de uma função, o uso só acontece quando ela for chamada,
+        // possivelmente depois da declaração
+        if !declaration.initialized && declaration.function_depth == function_depth {
+            self.errors.push(ResolveError::UseBeforeDeclaration {
+                name: name.node.clone(),
+                span: name.span.clone(),
+                declaration: declaration.span.clone(),
+            });
+        }
+
+        // Uma arrow function captura a variável ao ser criada, então qualquer
+        // uso dentro dela precisa que a variável já tenha um valor
+        let captured = declaration.function_depth < function_depth;
+        if declaration.initialized && !declaration.assigned && (!write || captured) {
+            self.unassigned_use(index, name);
+        }
+
+        let declaration_depth = self.scopes[index].get(&name.node)?.function_depth;
+        for (depth, captures) in &mut self.captures {
+            if declaration_depth < *depth {
+                *captures.entry((index, name.node.clone())).or_default() |= write;
+            }
+        }
+        // Dentro de uma arrow function, o uso só acontece quando ela é chamada
+        if self.captures.is_empty() {
+            self.check_captures(index, name, write);
+        }
+        self.lookup(&name.node)
+    }
+
+    // Uma closure que captura variáveis as pega emprestadas até o seu último
+    // uso; nesse intervalo, o Rust não permite alterar uma variável que ela
+    // usa, nem usar uma variável que ela altera.
+    fn check_captures(&mut self, index: usize, name: &Ident, write: bool) {
+        self.uses += 1;
+        let position = self.uses;
+
+        let Some(declaration) = self.scopes[index].get_mut(&name.node) else {
+            return;
+        };
+        if declaration.captures.is_empty() {
+            let access = Access {
+                capture: (index, name.node.clone()),
+                span: name.span.clone(),
+                write,
+                position,
+                closure: None,
+            };
+            self.check_access(access);
+            return;
+        }
+
+        // Chamar uma closure usa as variáveis que ela captura
+        if let Some(conflict) = declaration.conflict.take() {
+            self.errors
+                .push(closure_conflict(conflict, &name.node, &name.span));
+        }
+        declaration.last_use = Some((position, name.span.clone()));
+        let captures: Vec<_> = declaration
+            .captures
+            .iter()
+            .map(|(capture, write)| (capture.clone(), *write))
+            .collect();
+        for (capture, write) in captures {
+            self.check_access(Access {
+                capture,
+                span: name.span.clone(),
+                write,
+                position,
+                closure: Some((index, name.node.clone())),
+            });
+        }
+    }
+
+    // Registra o conflito do acesso com as closures que capturam a variável,
+    // com exceção da closure que fez o acesso
+    fn check_access(&mut self, access: Access) {
+        for (scope_index, scope) in self.scopes.iter_mut().enumerate() {
+            for (closure, declaration) in scope.iter_mut() {
+                let is_accessor = access
+                    .closure
+                    .as_ref()
+                    .is_some_and(|(index, name)| *index == scope_index && name == closure);
+                let Some(captured_write) = declaration.captures.get(&access.capture) else {
+                    continue;
+                };
+                if is_accessor || declaration.conflict.is_some() {
+                    continue;
+                }
+                if access.write || *captured_write {
+                    declaration.conflict = Some(Conflict {
+                        name: access.capture.1.clone(),
+                        span: access.span.clone(),
+                        write: access.write,
+                        position: access.position,
+                        closure: access.closure.as_ref().map(|(_, name)| name.clone()),
+                    });
+                }
+            }
+        }
+    }
+
+    // Em um laço, a closure usada no início do corpo é usada de novo depois
+    // de um conflito no fim dele, na próxima repetição
+    fn check_loop(&mut self, start: usize) {
+        for scope in &mut self.scopes {
+            for (closure, declaration) in scope.iter_mut() {
+                let Some((last_use, span)) = &declaration.last_use else {
+                    continue;
+                };
+                let in_loop = declaration
+                    .conflict
+                    .as_ref()
+                    .is_some_and(|conflict| conflict.position > start);
+                if *last_use > start && in_loop {
+                    let span = span.clone();
+                    if let Some(conflict) = declaration.conflict.take() {
+                        self.errors.push(closure_conflict(conflict, closure, &span));
+                    }
+                }
+            }
+        }
+    }
+
+    fn unassigned_use(&mut self, index: usize, name: &Ident) {
+        let Some(declaration) = self.scopes[index].get_mut(&name.node) else {
+            return;
+        };
+        // O erro é mostrado uma vez só para cada variável
+        declaration.assigned = true;
+        self.errors.push(ResolveError::UnassignedVariable {
+            name: name.node.clone(),
+            span: name.span.clone(),
+            declaration: declaration.span.clone(),
+        });
+    }
+
+    // `compound` para `+=` e semelhantes, `++` e `--`, que também leem a variável
+    fn resolve_assignment(&mut self, name: &Ident, compound: bool) {
+        let function_depth = self.function_depth;
+        let Some(declaration) = self.resolve_use(name, true) else {
+            self.errors.push(ResolveError::UndeclaredAssignment {
+                name: name.node.clone(),
+                span: name.span.clone(),
+            });
+            return;
+        };
+        let captured = declaration.function_depth < function_depth;
+        if declaration.is_const {
+            let declaration = declaration.span.clone();
+            self.errors.push(ResolveError::AssignToConst {
+                name: name.node.clone(),
+                span: name.span.clone(),
+                declaration,
+            });
+        }
+
+        let Some(index) = self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.contains_key(&name.node))
+        else {
+            return;
+        };
+        let Some(declaration) = self.scopes[index].get_mut(&name.node) else {
+            return;
+        };
+        if !declaration.initialized || declaration.assigned || captured {
+            return;
+        }
+        if compound {
+            self.unassigned_use(index, name);
+        } else {
+            declaration.assigned = true;
+        }
+    }
+}
+
+// O bloco sempre termina em um `return`, `break` ou `continue`
+fn diverges(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match &stmt.node {
+        Statement::Return(_) | Statement::Break | Statement::Continue => true,
+        Statement::IfStatement {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } => diverges(then_branch) && diverges(else_branch),
+        _ => false,
+    })
+}
+
+fn closure_conflict(conflict: Conflict, closure: &str, closure_use: &Span) -> ResolveError {
+    ResolveError::CapturedByClosure {
+        name: conflict.name,
+        closure: closure.to_string(),
+        span: conflict.span,
+        write: conflict.write,
+        by: conflict.closure,
+        closure_use: closure_use.clone(),
+    }
+}
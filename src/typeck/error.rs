@@ -0,0 +1,196 @@
+use crate::ast::{BinaryOperator, Type};
+use crate::diagnostics::{Diagnostic, ToDiagnostic};
+use logos::Span;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TypeError {
+    #[error("tipos incompatíveis: esperava-se `{expected}`, mas foi encontrado `{found}`")]
+    TypeMismatch {
+        expected: Type,
+        found: Type,
+        span: Span,
+    },
+    #[error("os ramos do condicional têm tipos incompatíveis: `{consequent}` e `{alternate}`")]
+    IncompatibleBranches {
+        consequent: Type,
+        alternate: Type,
+        span: Span,
+    },
+    #[error("não é possível comparar `{left}` com `{right}`")]
+    IncomparableTypes {
+        operator: BinaryOperator,
+        left: Type,
+        right: Type,
+        span: Span,
+    },
+    #[error("o operador `{operator}` não pode ser aplicado a `{left}` e `{right}`")]
+    InvalidOperands {
+        operator: BinaryOperator,
+        left: Type,
+        right: Type,
+        span: Span,
+    },
+    #[error("o operador `{operator}` não pode ser aplicado a `{found}`")]
+    InvalidOperand {
+        operator: String,
+        found: Type,
+        span: Span,
+    },
+    #[error("o `{operator}` não pode comparar valores do tipo `{found}`")]
+    ReferenceComparison {
+        operator: BinaryOperator,
+        found: Type,
+        span: Span,
+    },
+    #[error("os operandos de `{operator}` têm tipos diferentes: `{left}` e `{right}`")]
+    LogicalOperandTypes {
+        operator: BinaryOperator,
+        left: Type,
+        right: Type,
+        span: Span,
+    },
+    #[error("um valor do tipo `{found}` não pode ser usado como condição")]
+    InvalidCondition { found: Type, span: Span },
+    #[error("não é possível chamar um valor do tipo `{found}`")]
+    NotCallable { found: Type, span: Span },
+    #[error("esperava-se {expected} argumento(s), mas foram passados {found}")]
+    ArgumentCount {
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    #[error("o `for...of` só percorre arrays, mas foi encontrado `{found}`")]
+    NotIterable { found: Type, span: Span },
+    #[error("`return` sem valor em uma função que retorna `{expected}`")]
+    MissingReturnValue { expected: Type, span: Span },
+    #[error("a função pode terminar sem retornar um valor do tipo `{expected}`")]
+    MissingReturn { expected: Type, span: Span },
+    #[error("não foi possível inferir o tipo de `{name}`")]
+    CannotInfer {
+        name: String,
+        span: Span,
+        // Falso quando a variável foi declarada sem valor e nunca recebeu um
+        has_value: bool,
+    },
+}
+
+impl TypeError {
+    pub fn span(&self) -> &Span {
+        match self {
+            TypeError::TypeMismatch { span, .. }
+            | TypeError::IncompatibleBranches { span, .. }
+            | TypeError::IncomparableTypes { span, .. }
+            | TypeError::InvalidOperands { span, .. }
+            | TypeError::InvalidOperand { span, .. }
+            | TypeError::LogicalOperandTypes { span, .. }
+            | TypeError::ReferenceComparison { span, .. }
+            | TypeError::InvalidCondition { span, .. }
+            | TypeError::NotCallable { span, .. }
+            | TypeError::ArgumentCount { span, .. }
+            | TypeError::NotIterable { span, .. }
+            | TypeError::MissingReturnValue { span, .. }
+            | TypeError::MissingReturn { span, .. }
+            | TypeError::CannotInfer { span, .. } => span,
+        }
+    }
+}
+
+impl ToDiagnostic for TypeError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.to_string());
+        match self {
+            TypeError::TypeMismatch { found, span, .. } => {
+                diagnostic.with_label(span.clone(), format!("este valor é do tipo `{}`", found))
+            }
+            TypeError::IncompatibleBranches { span, .. } => {
+                diagnostic.with_label(span.clone(), "os dois ramos precisam ter o mesmo tipo")
+            }
+            TypeError::IncomparableTypes { operator, span, .. } => {
+                let diagnostic =
+                    diagnostic.with_label(span.clone(), "os operandos precisam ter o mesmo tipo");
+                match operator {
+                    BinaryOperator::Equal | BinaryOperator::NotEqual => diagnostic.with_note(format!(
+                        "o `{}` não converte os operandos como no JavaScript; converta um dos lados explicitamente",
+                        operator
+                    )),
+                    _ => diagnostic,
+                }
+            }
+            TypeError::InvalidOperands { operator, span, .. } => {
+                let label = match operator {
+                    BinaryOperator::Add => "some dois `number` ou concatene com uma `string`",
+                    _ if operator.is_comparison() => "compare dois `number` ou duas `string`",
+                    _ => "os dois operandos precisam ser `number`",
+                };
+                diagnostic.with_label(span.clone(), label)
+            }
+            TypeError::InvalidOperand { span, .. } => {
+                diagnostic.with_label(span.clone(), "esperava-se um `number`")
+            }
+            TypeError::ReferenceComparison { found, span, .. } => {
+                let diagnostic = diagnostic.with_label(
+                    span.clone(),
+                    "o JavaScript compara pela referência, e não pelo conteúdo",
+                );
+                match found {
+                    Type::Array(_) => diagnostic.with_note("compare os elementos um a um"),
+                    _ => diagnostic,
+                }
+            }
+            TypeError::LogicalOperandTypes { operator, span, .. } => diagnostic
+                .with_label(span.clone(), "o resultado seria um dos dois operandos")
+                .with_note(format!(
+                    "use operandos do mesmo tipo, ou `{}` só em uma condição",
+                    operator
+                )),
+            TypeError::InvalidCondition { found, span } => {
+                let diagnostic = diagnostic.with_label(span.clone(), "condição inválida");
+                match found {
+                    Type::Void => diagnostic.with_note("funções `void` não devolvem um valor"),
+                    Type::Function { .. } => diagnostic.with_note(
+                        "uma função é sempre verdadeira; talvez falte chamá-la com `()`",
+                    ),
+                    _ => diagnostic,
+                }
+            }
+            TypeError::NotCallable { span, .. } => {
+                diagnostic.with_label(span.clone(), "não é uma função")
+            }
+            TypeError::ArgumentCount { span, .. } => {
+                diagnostic.with_label(span.clone(), "número de argumentos incorreto")
+            }
+            TypeError::NotIterable { span, .. } => {
+                diagnostic.with_label(span.clone(), "não é um array")
+            }
+            TypeError::CannotInfer {
+                name,
+                span,
+                has_value,
+            } => {
+                if *has_value {
+                    diagnostic
+                        .with_label(span.clone(), "o tipo do valor inicial é desconhecido")
+                        .with_note(format!(
+                            "anote o tipo na declaração, como em `let {}: number[] = []`",
+                            name
+                        ))
+                } else {
+                    diagnostic
+                        .with_label(span.clone(), "declarada sem valor e sem atribuições")
+                        .with_note(format!(
+                            "anote o tipo na declaração, como em `let {}: number;`",
+                            name
+                        ))
+                }
+            }
+            TypeError::MissingReturnValue { expected, span } => diagnostic.with_label(
+                span.clone(),
+                format!("esperava-se um valor do tipo `{}`", expected),
+            ),
+            TypeError::MissingReturn { span, .. } => diagnostic
+                .with_label(span.clone(), "nem todos os caminhos terminam em `return`")
+                .with_note("adicione um `return` no fim da função"),
+        }
+    }
+}
@@ -0,0 +1,592 @@
+mod error;
+pub use error::TypeError;
+
+use crate::ast::{
+    function_type, ArrowBody, BinaryOperator, Expr, Expression, Ident, Parameter, Statement, Stmt,
+    Type, UnaryOperator,
+};
+use logos::Span;
+use std::collections::HashMap;
+
+// Calcula o tipo de cada expressão e verifica se ele é usado de forma
+// compatível. Um tipo desconhecido (`None`), como o de um parâmetro de arrow
+// function sem anotação, é aceito em qualquer posição.
+pub struct TypeChecker {
+    scopes: Vec<HashMap<String, Variable>>,
+    // Tipo de retorno de cada função sendo verificada, quando declarado
+    return_types: Vec<Option<Type>>,
+    errors: Vec<TypeError>,
+}
+
+struct Variable {
+    ty: Option<Type>,
+    // Declarada sem tipo e sem valor; o tipo vem da primeira atribuição
+    deferred: bool,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            scopes: vec![HashMap::new()],
+            return_types: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    // Verifica as declarações de um arquivo; como no `Resolver`, as do topo
+    // continuam visíveis para os arquivos seguintes.
+    pub fn check(&mut self, stmts: &mut [Stmt]) -> Result<(), Vec<TypeError>> {
+        self.check_statements(stmts);
+
+        let mut errors = std::mem::take(&mut self.errors);
+        errors.sort_by_key(|error| error.span().start);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn declare(&mut self, name: &Ident, ty: Option<Type>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            let variable = Variable {
+                ty,
+                deferred: false,
+            };
+            scope.insert(name.node.clone(), variable);
+        }
+    }
+
+    fn declare_parameters(&mut self, params: &[Parameter]) {
+        for param in params {
+            self.declare(&param.name, param.type_annotation.clone());
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Type> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .and_then(|variable| variable.ty.clone())
+    }
+
+    // Registra um erro se o tipo encontrado, quando conhecido, não é o esperado
+    fn expect_type(&mut self, expected: &Type, found: Option<Type>, span: &Span) {
+        if let Some(found) = found.filter(|found| found != expected) {
+            self.errors.push(TypeError::TypeMismatch {
+                expected: expected.clone(),
+                found,
+                span: span.clone(),
+            });
+        }
+    }
+
+    fn check_block(&mut self, stmts: &mut [Stmt]) {
+        self.scopes.push(HashMap::new());
+        self.check_statements(stmts);
+        self.scopes.pop();
+    }
+
+    // O Rust recusa uma função com retorno que pode chegar ao fim do corpo
+    fn check_returns(&mut self, return_type: &Option<Type>, body: &[Stmt], span: &Span) {
+        match return_type {
+            Some(expected) if *expected != Type::Void && !always_returns(body) => {
+                self.errors.push(TypeError::MissingReturn {
+                    expected: expected.clone(),
+                    span: span.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn check_statements(&mut self, stmts: &mut [Stmt]) {
+        // Funções podem ser chamadas antes da declaração
+        for stmt in stmts.iter() {
+            if let Statement::FunctionDeclaration {
+                name,
+                params,
+                return_type,
+                ..
+            } = &stmt.node
+            {
+                self.declare(name, function_type(params, return_type));
+            }
+        }
+        for stmt in stmts.iter_mut() {
+            self.check_statement(stmt);
+        }
+        for stmt in stmts.iter_mut() {
+            self.infer_declaration(stmt);
+        }
+    }
+
+    // Preenche a anotação de uma declaração do escopo atual com o tipo
+    // inferido do valor inicial ou da primeira atribuição
+    fn infer_declaration(&mut self, stmt: &mut Stmt) {
+        let Statement::VariableDeclaration {
+            name,
+            type_annotation: type_annotation @ None,
+            value,
+            ..
+        } = &mut stmt.node
+        else {
+            return;
+        };
+        let inferred = self
+            .scopes
+            .last()
+            .and_then(|scope| scope.get(&name.node))
+            .and_then(|variable| variable.ty.clone());
+        match inferred {
+            Some(ty) => *type_annotation = Some(ty),
+            // Closures não precisam de um tipo nomeável no Rust
+            None if matches!(
+                value.as_ref().map(|value| &value.node),
+                Some(Expression::ArrowFunction { .. })
+            ) => {}
+            None => self.errors.push(TypeError::CannotInfer {
+                name: name.node.clone(),
+                span: name.span.clone(),
+                has_value: value.is_some(),
+            }),
+        }
+    }
+
+    fn check_statement(&mut self, stmt: &mut Stmt) {
+        match &mut stmt.node {
+            Statement::Console { args, .. } => {
+                for arg in args {
+                    self.check_expression(arg);
+                }
+            }
+            Statement::VariableDeclaration {
+                name,
+                type_annotation,
+                value,
+                ..
+            } => {
+                let found = value
+                    .as_mut()
+                    .and_then(|value| self.check_expression(value));
+                if let (Some(expected), Some(value)) = (&type_annotation, &value) {
+                    self.expect_type(expected, found.clone(), &value.span);
+                }
+                // Sem tipo nem valor, o tipo vem da primeira atribuição
+                let variable = Variable {
+                    deferred: type_annotation.is_none() && value.is_none(),
+                    ty: type_annotation.clone().or(found),
+                };
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.insert(name.node.clone(), variable);
+                }
+            }
+            Statement::IfStatement {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.check_condition(condition);
+                self.check_block(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.check_block(else_branch);
+                }
+            }
+            Statement::WhileStatement { condition, body } => {
+                self.check_condition(condition);
+                self.check_block(body);
+            }
+            Statement::ForStatement {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.scopes.push(HashMap::new());
+                if let Some(init) = init {
+                    self.check_statement(init);
+                }
+                if let Some(condition) = condition {
+                    self.check_condition(condition);
+                }
+                if let Some(update) = update {
+                    self.check_expression(update);
+                }
+                self.check_block(body);
+                if let Some(init) = init {
+                    self.infer_declaration(init);
+                }
+                self.scopes.pop();
+            }
+            Statement::ForOfStatement {
+                variable,
+                iterable,
+                body,
+                ..
+            } => {
+                let element = match self.check_expression(iterable) {
+                    Some(Type::Array(element)) => Some(*element),
+                    Some(found) => {
+                        self.errors.push(TypeError::NotIterable {
+                            found,
+                            span: iterable.span.clone(),
+                        });
+                        None
+                    }
+                    None => None,
+                };
+                self.scopes.push(HashMap::new());
+                self.declare(variable, element);
+                self.check_block(body);
+                self.scopes.pop();
+            }
+            Statement::Break | Statement::Continue => {}
+            Statement::Assignment { name, op, value } => {
+                self.check_assignment(name, op, value);
+            }
+            Statement::FunctionDeclaration {
+                name,
+                params,
+                return_type,
+                body,
+            } => {
+                self.scopes.push(HashMap::new());
+                self.declare_parameters(params);
+                self.return_types.push(return_type.clone());
+                self.check_statements(body);
+                self.check_returns(return_type, body, &name.span);
+                self.return_types.pop();
+                self.scopes.pop();
+            }
+            Statement::Return(value) => {
+                let found = value
+                    .as_mut()
+                    .and_then(|value| self.check_expression(value));
+                let Some(Some(expected)) = self.return_types.last().cloned() else {
+                    return;
+                };
+                match value {
+                    Some(value) => self.expect_type(&expected, found, &value.span),
+                    None if expected != Type::Void => {
+                        self.errors.push(TypeError::MissingReturnValue {
+                            expected,
+                            span: stmt.span.clone(),
+                        });
+                    }
+                    None => {}
+                }
+            }
+            Statement::Expression(expr) => {
+                self.check_expression(expr);
+            }
+        }
+    }
+
+    // Qualquer valor segue a regra de verdade do JavaScript, exceto os que
+    // nunca fazem sentido como condição
+    fn check_condition(&mut self, expr: &mut Expr) {
+        // Em uma condição, `&&` e `||` só usam o valor de verdade dos
+        // operandos, que podem ter tipos diferentes
+        if let Expression::BinaryOp { left, op, right } = &mut expr.node {
+            if op.is_logical() {
+                self.check_condition(left);
+                self.check_condition(right);
+                return;
+            }
+        }
+        if let Some(found @ (Type::Void | Type::Function { .. })) = self.check_expression(expr) {
+            self.errors.push(TypeError::InvalidCondition {
+                found,
+                span: expr.span.clone(),
+            });
+        }
+    }
+
+    fn check_assignment(
+        &mut self,
+        name: &Ident,
+        op: &Option<BinaryOperator>,
+        value: &mut Expr,
+    ) -> Option<Type> {
+        let found = self.check_expression(value);
+        let variable = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(&name.node));
+        if let Some(variable) = variable.filter(|variable| variable.deferred) {
+            variable.deferred = false;
+            if op.is_none() {
+                variable.ty = found.clone();
+            }
+        }
+        let target = self.lookup(&name.node);
+        let result = match op {
+            None => found,
+            Some(op) => {
+                let span = name.span.start..value.span.end;
+                self.binary_type(op, target.clone(), found, &span)
+            }
+        };
+        if let Some(target) = &target {
+            self.expect_type(target, result, &value.span);
+        }
+        target
+    }
+
+    fn check_expression(&mut self, expr: &mut Expr) -> Option<Type> {
+        match &mut expr.node {
+            Expression::StringLiteral(_) => Some(Type::String),
+            Expression::NumberLiteral(_) => Some(Type::Number),
+            Expression::BooleanLiteral(_) => Some(Type::Boolean),
+            Expression::Identifier(name) => self.lookup(name),
+            Expression::BinaryOp { left, op, right } => {
+                let left = self.check_expression(left);
+                let right = self.check_expression(right);
+                self.binary_type(op, left, right, &expr.span)
+            }
+            Expression::Assignment { name, op, value } => self.check_assignment(name, op, value),
+            Expression::Conditional {
+                condition,
+                consequent,
+                alternate,
+            } => {
+                self.check_condition(condition);
+                let consequent_type = self.check_expression(consequent);
+                let alternate_type = self.check_expression(alternate);
+                match (consequent_type, alternate_type) {
+                    (Some(consequent_type), Some(alternate_type))
+                        if consequent_type != alternate_type =>
+                    {
+                        self.errors.push(TypeError::IncompatibleBranches {
+                            consequent: consequent_type.clone(),
+                            alternate: alternate_type,
+                            span: consequent.span.start..alternate.span.end,
+                        });
+                        Some(consequent_type)
+                    }
+                    (consequent_type, alternate_type) => consequent_type.or(alternate_type),
+                }
+            }
+            Expression::Update { name, op, .. } => {
+                self.check_number_operand(&op.to_string(), self.lookup(&name.node), &name.span);
+                Some(Type::Number)
+            }
+            Expression::ArrayLiteral(elements) => {
+                // O primeiro elemento de tipo conhecido define o tipo do array
+                let mut element_type: Option<Type> = None;
+                for element in elements {
+                    let found = self.check_expression(element);
+                    match &element_type {
+                        Some(expected) => {
+                            let expected = expected.clone();
+                            self.expect_type(&expected, found, &element.span);
+                        }
+                        None => element_type = found,
+                    }
+                }
+                element_type.map(|element| Type::Array(Box::new(element)))
+            }
+            Expression::Unary { op, operand } => match op {
+                UnaryOperator::Not => {
+                    self.check_condition(operand);
+                    Some(Type::Boolean)
+                }
+                UnaryOperator::Negate | UnaryOperator::BitNot => {
+                    let found = self.check_expression(operand);
+                    self.check_number_operand(&op.to_string(), found, &expr.span);
+                    Some(Type::Number)
+                }
+            },
+            Expression::Call { callee, args } => {
+                let callee_type = self.check_expression(callee);
+                let arg_types: Vec<_> = args.iter_mut().map(|arg| self.check_expression(arg)).collect();
+                match callee_type? {
+                    Type::Function {
+                        params,
+                        return_type,
+                    } => {
+                        if params.len() != args.len() {
+                            self.errors.push(TypeError::ArgumentCount {
+                                expected: params.len(),
+                                found: args.len(),
+                                span: expr.span.clone(),
+                            });
+                        }
+                        for ((param, arg), found) in params.iter().zip(args).zip(arg_types) {
+                            self.expect_type(param, found, &arg.span);
+                        }
+                        Some(*return_type)
+                    }
+                    found => {
+                        self.errors.push(TypeError::NotCallable {
+                            found,
+                            span: callee.span.clone(),
+                        });
+                        None
+                    }
+                }
+            }
+            Expression::ArrowFunction {
+                params,
+                return_type,
+                body,
+            } => {
+                self.scopes.push(HashMap::new());
+                self.declare_parameters(params);
+                self.return_types.push(return_type.clone());
+                let body_type = match body {
+                    ArrowBody::Expression(expr) => {
+                        let found = self.check_expression(expr);
+                        if let Some(expected) = return_type {
+                            self.expect_type(expected, found.clone(), &expr.span);
+                        }
+                        found
+                    }
+                    ArrowBody::Block(stmts) => {
+                        self.check_statements(stmts);
+                        self.check_returns(return_type, stmts, &expr.span);
+                        None
+                    }
+                };
+                self.return_types.pop();
+                self.scopes.pop();
+
+                // Sem anotação, o retorno de `x => expr` é o tipo da expressão
+                function_type(params, &return_type.clone().or(body_type))
+            }
+        }
+    }
+
+    fn check_number_operand(&mut self, operator: &str, found: Option<Type>, span: &Span) {
+        if let Some(found) = found.filter(|found| *found != Type::Number) {
+            self.errors.push(TypeError::InvalidOperand {
+                operator: operator.to_string(),
+                found,
+                span: span.clone(),
+            });
+        }
+    }
+
+    // Tipo do resultado de `left op right`, registrando um erro quando os
+    // operandos não são aceitos pelo operador
+    fn binary_type(
+        &mut self,
+        op: &BinaryOperator,
+        left: Option<Type>,
+        right: Option<Type>,
+        span: &Span,
+    ) -> Option<Type> {
+        let is_string = |ty: &Option<Type>| *ty == Some(Type::String);
+        let result = match op {
+            BinaryOperator::Add if is_string(&left) || is_string(&right) => Some(Type::String),
+            BinaryOperator::And | BinaryOperator::Or => left.clone().or(right.clone()),
+            _ if op.is_comparison() => Some(Type::Boolean),
+            _ => Some(Type::Number),
+        };
+
+        let (Some(left), Some(right)) = (left, right) else {
+            return result;
+        };
+        let valid = match op {
+            // `string + x` concatena qualquer valor primitivo
+            BinaryOperator::Add => match (&left, &right) {
+                (Type::String, other) | (other, Type::String) => {
+                    matches!(other, Type::String | Type::Number | Type::Boolean)
+                }
+                _ => left == Type::Number && right == Type::Number,
+            },
+            // Como valor, `a || b` é um dos operandos, e não um `boolean`
+            BinaryOperator::And | BinaryOperator::Or if left != right => {
+                self.errors.push(TypeError::LogicalOperandTypes {
+                    operator: op.clone(),
+                    left,
+                    right,
+                    span: span.clone(),
+                });
+                return result;
+            }
+            BinaryOperator::And | BinaryOperator::Or => true,
+            // Comparar valores de tipos diferentes é um erro, inclusive com o
+            // `==`, em vez da conversão implícita do JavaScript
+            _ if op.is_comparison() && left != right => {
+                self.errors.push(TypeError::IncomparableTypes {
+                    operator: op.clone(),
+                    left,
+                    right,
+                    span: span.clone(),
+                });
+                return result;
+            }
+            // Arrays e funções são iguais só quando são o mesmo objeto, o que
+            // não existe nos valores gerados em Rust
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::StrictEqual
+            | BinaryOperator::StrictNotEqual
+                if matches!(left, Type::Array(_) | Type::Function { .. }) =>
+            {
+                self.errors.push(TypeError::ReferenceComparison {
+                    operator: op.clone(),
+                    found: left,
+                    span: span.clone(),
+                });
+                return result;
+            }
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::StrictEqual
+            | BinaryOperator::StrictNotEqual => true,
+            _ if op.is_comparison() => matches!(left, Type::Number | Type::String),
+            _ => left == Type::Number && right == Type::Number,
+        };
+        if !valid {
+            self.errors.push(TypeError::InvalidOperands {
+                operator: op.clone(),
+                left,
+                right,
+                span: span.clone(),
+            });
+        }
+        result
+    }
+}
+
+// Verdadeiro quando todo caminho pelo bloco termina em `return`. Um `for (;;)`
+// sem `break` também conta, porque é gerado como `loop` e nunca termina.
+fn always_returns(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match &stmt.node {
+        Statement::Return(_) => true,
+        Statement::IfStatement {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } => always_returns(then_branch) && always_returns(else_branch),
+        Statement::ForStatement {
+            condition: None,
+            body,
+            ..
+        } => !breaks(body),
+        _ => false,
+    })
+}
+
+// Procura um `break` do próprio laço, ignorando os dos laços internos
+fn breaks(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match &stmt.node {
+        Statement::Break => true,
+        Statement::IfStatement {
+            then_branch,
+            else_branch,
+            ..
+        } => breaks(then_branch) || else_branch.as_deref().is_some_and(breaks),
+        _ => false,
+    })
+}
@@ -0,0 +1,618 @@
+// Compila e executa programas TypeScript com o comando `run` e confere a
+// saída, que deve ser a mesma do Node.
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn run(name: &str, source: &str) -> String {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("programa.ts");
+    fs::write(&file, source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_js-compiler"))
+        .arg("run")
+        .arg("-o")
+        .arg(dir.join("out"))
+        .arg(&file)
+        // O `run` deve achar o executável mesmo com outro diretório `target`
+        .env("CARGO_TARGET_DIR", dir.join("target"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// Compila um programa que deve ser rejeitado e devolve os erros exibidos
+fn compile_error(name: &str, source: &str) -> String {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("programa.ts");
+    fs::write(&file, source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_js-compiler"))
+        .arg("build")
+        .arg("-o")
+        .arg(dir.join("out"))
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn for_with_break_and_continue() {
+    let output = run(
+        "for_with_break_and_continue",
+        r#"
+let i = 0;
+for (i = 0; i < 10; i = i + 1) {
+    if (i == 2) { continue; }
+    if (i == 5) { break; }
+    console.log(i);
+}
+let k = 0;
+for (; k < 10; k += 3) {
+    if (k == 3) { continue; }
+    if (k == 9) { break; }
+    console.log(k);
+}
+"#,
+    );
+    assert_eq!(output, "0\n1\n3\n4\n0\n6\n");
+}
+
+#[test]
+fn console_log_of_iterated_elements() {
+    let output = run(
+        "console_log_of_iterated_elements",
+        r#"
+const nomes: string[] = ["Ana", "Bia"];
+for (const nome of nomes) {
+    console.log(nome);
+}
+console.log(nomes);
+const matriz = [[1, 2], [3]];
+for (const linha of matriz) {
+    console.log(linha);
+}
+console.log(matriz);
+"#,
+    );
+    assert_eq!(
+        output,
+        "Ana\nBia\n[ 'Ana', 'Bia' ]\n[ 1, 2 ]\n[ 3 ]\n[ [ 1, 2 ], [ 3 ] ]\n"
+    );
+}
+
+#[test]
+fn for_of_elements_are_values() {
+    let output = run(
+        "for_of_elements_are_values",
+        r#"
+function saudar(nome: string): string {
+    return "Oi, " + nome;
+}
+const nomes: string[] = ["Ana", "Bia"];
+for (const nome of nomes) {
+    let copia: string = nome;
+    console.log(copia);
+}
+for (const nome of nomes) {
+    console.log(saudar(nome));
+}
+console.log(nomes);
+"#,
+    );
+    assert_eq!(output, "Ana\nBia\nOi, Ana\nOi, Bia\n[ 'Ana', 'Bia' ]\n");
+}
+
+#[test]
+fn not_of_integer_variable() {
+    let output = run(
+        "not_of_integer_variable",
+        r#"
+let i = 5;
+i++;
+console.log(!i);
+if (!i) {
+    console.log("zero");
+} else {
+    console.log("não zero");
+}
+"#,
+    );
+    assert_eq!(output, "false\nnão zero\n");
+}
+
+#[test]
+fn string_concatenation() {
+    let output = run(
+        "string_concatenation",
+        r#"
+let s = "x";
+let a = 1;
+let b = 2.5;
+let ok = true;
+console.log(s + "y");
+console.log("total: " + (a + b));
+console.log(a + "z");
+console.log("b" + "{c}");
+console.log(1 + 2 + "x" + a + b + ok + s);
+let t = s + s;
+t += "!";
+console.log(t, s);
+console.log("v: " + 1 / 0);
+console.log("v: " + 1e21);
+console.log("v: " + -0);
+console.log("" + 1e-7);
+let u = "a";
+u += 1 / 0;
+u += a;
+console.log(u);
+"#,
+    );
+    assert_eq!(
+        output,
+        "xy\ntotal: 3.5\n1z\nb{c}\n3x12.5truex\nxx! x\n\
+         v: Infinity\nv: 1e+21\nv: 0\n1e-7\naInfinity1\n"
+    );
+}
+
+#[test]
+fn function_declarations_call_functions() {
+    let output = run(
+        "function_declarations_call_functions",
+        r#"
+function dobro(x: number): number {
+    return x * 2;
+}
+function calcular(y: number): number {
+    function metade(z: number): number {
+        return z / 2;
+    }
+    const mais_um = (): number => y + 1;
+    return dobro(y) + metade(y) + mais_um();
+}
+console.log(calcular(4));
+"#,
+    );
+    assert_eq!(output, "15\n");
+}
+
+#[test]
+fn closure_called_after_captures_are_used() {
+    let output = run(
+        "closure_called_after_captures_are_used",
+        r#"
+let c = 0;
+const inc = () => { c++; };
+inc();
+inc();
+console.log(c);
+let nome = "Ana";
+const s = () => console.log("Oi " + nome);
+s();
+console.log(nome);
+s();
+nome = "Bia";
+console.log(nome);
+"#,
+    );
+    assert_eq!(output, "2\nOi Ana\nAna\nOi Ana\nBia\n");
+}
+
+#[test]
+fn closure_called_after_conflicting_use() {
+    let errors = compile_error(
+        "closure_called_after_conflicting_use",
+        r#"
+let c = 0;
+const inc = () => { c++; };
+inc();
+console.log(c);
+inc();
+let nome = "Ana";
+const s = () => console.log("Oi " + nome);
+s();
+nome = "Bia";
+s();
+let i = 0;
+const dobra = () => { i = i * 2; };
+while (i < 10) {
+    dobra();
+    i++;
+}
+"#,
+    );
+    assert!(errors.contains("`c` não pode ser usada enquanto a closure `inc` a captura"));
+    assert!(errors.contains("`nome` não pode ser alterada enquanto a closure `s` a captura"));
+    assert!(errors.contains("`i` não pode ser usada enquanto a closure `dobra` a captura"));
+}
+
+#[test]
+fn console_format_specifiers() {
+    let output = run(
+        "console_format_specifiers",
+        r#"
+const nome = "Ana";
+const xs = [1, 2, 3];
+console.log("%s tem %d anos", nome, 42.5);
+console.log("%i e %f", 42.5, "3.75abc");
+console.log("%d %d %d", "0x1f", true, "  12  ");
+console.log("%s | %O | %j", xs, nome, xs);
+console.log("100%% e %c fim", "color: red");
+console.log("sem args %s %d");
+console.log("%s %s", "só um");
+console.log("%x %s", 1, 2);
+console.log("{%s}", "a", "extra");
+"#,
+    );
+    assert_eq!(
+        output,
+        "Ana tem 42.5 anos\n42 e 3.75\n31 1 12\n[ 1, 2, 3 ] | 'Ana' | [1,2,3]\n\
+         100% e  fim\nsem args %s %d\nsó um %s\n%x 1 2\n{a} extra\n"
+    );
+}
+
+#[test]
+fn every_invalid_escape_is_reported() {
+    let errors = compile_error(
+        "every_invalid_escape_is_reported",
+        r#"console.log("\x4 \u{110000} \07");
+"#,
+    );
+    assert!(errors.contains("sequência de escape inválida `\\x4`"));
+    assert!(errors.contains("sequência de escape inválida `\\u{110000}`"));
+    assert!(errors.contains("sequência de escape inválida `\\07`"));
+    assert!(errors.contains("a compilação falhou com 3 erro(s)"));
+}
+
+#[test]
+fn crlf_line_endings() {
+    let output = run("crlf_line_endings", "let x = 1;\r\nconsole.log(x);\r\n");
+    assert_eq!(output, "1\n");
+}
+
+#[test]
+fn unexpected_character_is_escaped() {
+    let errors = compile_error("unexpected_character_is_escaped", "let y = 1;\u{7}\n");
+    assert!(errors.contains("caractere inesperado `\\u{7}`"));
+}
+
+#[test]
+fn logical_operators_keep_operand_values() {
+    let output = run(
+        "logical_operators_keep_operand_values",
+        r#"
+let s = "" || "b";
+console.log(s, 3 && 4, 0 || 7, true || false);
+if (0 || "a") { console.log("sim"); }
+console.log(!(0 || "a"));
+"#,
+    );
+    assert_eq!(output, "b 4 7 true\nsim\nfalse\n");
+}
+
+#[test]
+fn logical_operands_of_different_types() {
+    let errors = compile_error(
+        "logical_operands_of_different_types",
+        r#"
+let n = 0 || "a";
+console.log("" || 5);
+"#,
+    );
+    assert!(errors.contains("os operandos de `||` têm tipos diferentes: `number` e `string`"));
+    assert!(errors.contains("os operandos de `||` têm tipos diferentes: `string` e `number`"));
+}
+
+#[test]
+fn bitwise_operators_convert_like_js() {
+    let output = run(
+        "bitwise_operators_convert_like_js",
+        r#"
+console.log(1e20 | 0);
+console.log(1 / 0 | 0);
+console.log(~(1 / 0));
+console.log(1 / 0 >>> 0);
+const x = -1.5;
+console.log(x | 0, x >>> 0, ~x, x << 31, 2.9 & 3, 4294967296.5 | 0);
+let i = 5;
+console.log(i | 2, i >>> 1, ~i, -i >>> 0);
+"#,
+    );
+    assert_eq!(
+        output,
+        "1661992960\n0\n-1\n0\n-1 4294967295 0 -2147483648 2 0\n7 2 -6 4294967291\n"
+    );
+}
+
+#[test]
+fn equality_of_arrays_and_functions() {
+    let errors = compile_error(
+        "equality_of_arrays_and_functions",
+        r#"
+console.log([1] === [1]);
+const a = [1, 2];
+console.log(a != [1, 2]);
+const f = (x: number) => x;
+console.log(f == f);
+"#,
+    );
+    assert!(errors.contains("o `===` não pode comparar valores do tipo `number[]`"));
+    assert!(errors.contains("o `!=` não pode comparar valores do tipo `number[]`"));
+    assert!(errors.contains("o `==` não pode comparar valores do tipo `(arg0: number) => number`"));
+}
+
+#[test]
+fn closures_sharing_captures() {
+    let output = run(
+        "closures_sharing_captures",
+        r#"
+let nome = "Ana";
+const s1 = () => console.log(nome);
+const s2 = () => console.log(nome);
+s2();
+s1();
+let c = 0;
+const a = () => { c++; };
+a();
+a();
+const b = () => { c++; };
+b();
+console.log(c);
+"#,
+    );
+    assert_eq!(output, "Ana\nAna\n3\n");
+}
+
+#[test]
+fn closures_changing_the_same_capture() {
+    let errors = compile_error(
+        "closures_changing_the_same_capture",
+        r#"
+let c = 0;
+const a = () => { c++; };
+const b = () => { c++; };
+a();
+b();
+a();
+"#,
+    );
+    assert!(errors.contains("`c` não pode ser alterada enquanto a closure `a` a captura"));
+    assert!(errors.contains("alterada pela closure `b` aqui"));
+}
+
+#[test]
+fn functions_passed_as_arguments() {
+    let output = run(
+        "functions_passed_as_arguments",
+        r#"
+function aplicar(f: (x: number) => number, v: number): number {
+    return f(v);
+}
+function duas(f: (x: number) => number, v: number): number {
+    return aplicar(f, aplicar(f, v));
+}
+function dobro(x: number): number {
+    return x * 2;
+}
+const mais = (x: number) => x + 1;
+console.log(duas(dobro, 3), duas(mais, 3), aplicar(mais, 1));
+"#,
+    );
+    assert_eq!(output, "12 5 2\n");
+}
+
+#[test]
+fn variables_assigned_on_every_path() {
+    let output = run(
+        "variables_assigned_on_every_path",
+        r#"
+let c = true;
+let x;
+if (c) { x = 1; } else { x = 2; }
+console.log(x);
+for (let i = 0; i < 2; i++) {
+    let t: number;
+    if (i == 0) { t = 1; } else { continue; }
+    console.log(t);
+}
+let r: number;
+r = c ? 1 : 2;
+const g = () => r;
+console.log(g());
+function h(a: boolean): number {
+    let k: number;
+    if (a) { k = 1; } else { return 0; }
+    return k;
+}
+console.log(h(true), h(false));
+"#,
+    );
+    assert_eq!(output, "1\n1\n1\n1 0\n");
+}
+
+#[test]
+fn variable_used_before_assignment() {
+    let errors = compile_error(
+        "variable_used_before_assignment",
+        r#"
+let c = true;
+let x;
+if (c) { x = 1; }
+console.log(x);
+let y: number;
+console.log(y);
+let z: number;
+z += 1;
+let w: number;
+const f = () => w;
+let v: number;
+while (c) { v = 1; c = false; }
+console.log(v);
+"#,
+    );
+    for name in ["x", "y", "z", "w", "v"] {
+        assert!(errors.contains(&format!(
+            "a variável `{}` é usada antes de receber um valor",
+            name
+        )));
+    }
+}
+
+#[test]
+fn variables_copied_from_other_variables() {
+    let output = run(
+        "variables_copied_from_other_variables",
+        r#"
+let a = "x";
+let b = a;
+console.log(a, b);
+let xs = [1, 2];
+let ys: number[] = [];
+ys = xs;
+console.log(xs, ys);
+"#,
+    );
+    assert_eq!(output, "x x\n[ 1, 2 ] [ 1, 2 ]\n");
+}
+
+#[test]
+fn functions_returning_on_every_path() {
+    let output = run(
+        "functions_returning_on_every_path",
+        r#"
+function sign(x: number): number {
+  if (x > 0) { return 1; } else { if (x < 0) { return -1; } else { return 0; } }
+}
+function first(xs: number[]): number {
+  for (;;) {
+    let y = 0;
+    for (const x of xs) { if (x > 1) { y = x; break; } }
+    return y;
+  }
+}
+const label = (x: number): string => { if (x > 0) { return "positivo"; } return "outro"; };
+console.log(sign(-3), first([2, 3]), label(1));
+"#,
+    );
+    assert_eq!(output, "-1 2 positivo\n");
+}
+
+#[test]
+fn function_missing_return() {
+    let errors = compile_error(
+        "function_missing_return",
+        r#"
+function g(x: number): number { if (x > 0) { return 1; } }
+const h = (x: number): string => { for (;;) { if (x > 0) { break; } return "a"; } };
+function v(x: number): void { if (x > 0) { return; } }
+console.log(g(1), h(1));
+v(1);
+"#,
+    );
+    assert!(errors.contains("a função pode terminar sem retornar um valor do tipo `number`"));
+    assert!(errors.contains("a função pode terminar sem retornar um valor do tipo `string`"));
+    assert!(errors.contains("2 erro(s)"));
+}
+
+#[test]
+fn console_log_of_empty_arrays() {
+    let output = run(
+        "console_log_of_empty_arrays",
+        r#"
+console.log([]);
+console.log([[]], [[], []], [[], [1]]);
+console.log("%s %j", [], [[]]);
+"#,
+    );
+    assert_eq!(output, "[]\n[ [] ] [ [], [] ] [ [], [ 1 ] ]\n[] [[]]\n");
+}
+
+#[test]
+fn lexer_and_parser_errors_are_reported_together() {
+    let errors = compile_error(
+        "lexer_and_parser_errors_are_reported_together",
+        r#"
+let x = @;
+let y = 1 +;
+let w = 2 @ 3;
+console.log(x y);
+let s = "abc
+"#,
+    );
+    assert!(errors.contains("caractere inesperado `@`"));
+    assert!(errors.contains("esperava-se uma expressão, mas foi encontrado `;`"));
+    assert!(errors.contains("esperava-se `)`, mas foi encontrado identificador `y`"));
+    assert!(errors.contains("string não terminada"));
+    assert!(errors.contains("5 erro(s)"));
+}
+
+#[test]
+fn scope_errors() {
+    let errors = compile_error(
+        "scope_errors",
+        r#"
+let a = 1;
+let a = 2;
+console.log(b);
+c = 3;
+console.log(d);
+let d = 4;
+const e = 5;
+e = 6;
+"#,
+    );
+    assert!(errors.contains("`a` já foi declarada neste escopo"));
+    assert!(errors.contains("a variável `b` não foi declarada"));
+    assert!(errors.contains("atribuição à variável `c`, que não foi declarada"));
+    assert!(errors.contains("a variável `d` foi usada antes de ser declarada"));
+    assert!(errors.contains("não é possível atribuir à constante `e`"));
+    assert!(errors.contains("5 erro(s)"));
+}
+
+#[test]
+fn type_errors() {
+    let errors = compile_error(
+        "type_errors",
+        r#"
+let n: number = "um";
+function soma(a: number, b: number): number { return a + b; }
+console.log(soma(1));
+console.log(soma(1, true));
+console.log(1 == "1");
+"#,
+    );
+    assert!(
+        errors.contains("tipos incompatíveis: esperava-se `number`, mas foi encontrado `string`")
+    );
+    assert!(errors.contains("esperava-se 2 argumento(s), mas foram passados 1"));
+    assert!(
+        errors.contains("tipos incompatíveis: esperava-se `number`, mas foi encontrado `boolean`")
+    );
+    assert!(errors.contains("não é possível comparar `number` com `string`"));
+    assert!(errors.contains("4 erro(s)"));
+}
+
+#[test]
+fn numbers_appended_to_strings() {
+    let output = run(
+        "numbers_appended_to_strings",
+        r#"
+let s = "";
+s += 0.5;
+s += -0;
+s += 1 / 0;
+s += 123456789012345680000000;
+console.log(s);
+"#,
+    );
+    assert_eq!(output, "0.50Infinity1.2345678901234569e+23\n");
+}